@@ -15,8 +15,8 @@ pub fn conway_bench(c: &mut Criterion) {
         );
 
         let mut life_like_game = life_like::AutomatonBuilder::new(1)
-            .init(|| life_like::Grid::default())
-            .map(|(x, y), _, cells| life_like::count_neighbors((x, y), 1, &cells))
+            .init(life_like::Grid::default)
+            .map(|(x, y), _, cells| life_like::count_neighbors((x, y), 1, cells))
             .run(|_, is_alive, neighbors_n| match is_alive {
                 Some(_) => (2..=3).contains(&neighbors_n).then_some(()),
                 None => (neighbors_n == 3).then_some(()),
@@ -31,7 +31,7 @@ pub fn conway_bench(c: &mut Criterion) {
 }
 
 fn format_pos(pos: (usize, usize)) -> String {
-    format!("({}, {})", pos.0.to_string(), pos.1.to_string())
+    format!("({}, {})", pos.0, pos.1)
 }
 
 fn prepare_game_generic(builder: generic::AutomatonBuilder) -> generic::Automaton<bool, usize> {
@@ -0,0 +1,83 @@
+use std::hash::{BuildHasher, RandomState};
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use lifers::engine::{hasher::PosBuildHasher, life_like};
+use rustc_hash::FxBuildHasher;
+
+const SOUP_SIZE: i64 = 64;
+const GENERATIONS: u32 = 100;
+
+/// Compares the hashers of the life-like grid on a random soup, which is run
+/// for a while beforehand to get a realistic mix of objects.
+pub fn hashers_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Hashers");
+    let soup = soup(SOUP_SIZE);
+
+    bench_hasher::<FxBuildHasher>(&mut group, "FxHash", &soup);
+    bench_hasher::<RandomState>(&mut group, "SipHash", &soup);
+    bench_hasher::<PosBuildHasher>(&mut group, "PosHash", &soup);
+}
+
+fn bench_hasher<H: BuildHasher + Default + Clone>(
+    group: &mut criterion::BenchmarkGroup<criterion::measurement::WallTime>,
+    name: &str,
+    soup: &[(i64, i64)],
+) {
+    let mut game = life_like::AutomatonBuilder::new(1)
+        .init_with_hasher(|| {
+            soup.iter()
+                .map(|pos| (*pos, ()))
+                .collect::<life_like::Grid<(), H>>()
+        })
+        .map(|pos, _, cells| life_like::count_neighbors(pos, 1, cells))
+        .run(|_, is_alive, neighbors_n| match is_alive {
+            Some(_) => (2..=3).contains(&neighbors_n).then_some(()),
+            None => (neighbors_n == 3).then_some(()),
+        });
+
+    game.run_for(GENERATIONS);
+
+    let checkpoint = game.snapshot();
+    let step_fn = |_, is_alive: Option<()>, neighbors_n: u32| match is_alive {
+        Some(_) => (2..=3).contains(&neighbors_n).then_some(()),
+        None => (neighbors_n == 3).then_some(()),
+    };
+
+    group.bench_with_input(
+        BenchmarkId::new(name, format!("{SOUP_SIZE}x{SOUP_SIZE}")),
+        &checkpoint,
+        |b, checkpoint| {
+            b.iter_batched(
+                || {
+                    life_like::Automaton::restore(checkpoint.clone(), step_fn, |pos, _, cells| {
+                        life_like::count_neighbors(pos, 1, cells)
+                    })
+                },
+                |mut game| game.step(),
+                BatchSize::SmallInput,
+            );
+        },
+    );
+}
+
+/// Returns the live cells of a pseudorandom soup with a density of 50%,
+/// centered on the origin.
+fn soup(size: i64) -> Vec<(i64, i64)> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+
+    (0..size)
+        .flat_map(|y| (0..size).map(move |x| (x, y)))
+        .filter(|_| {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+
+            state & 1 == 1
+        })
+        .map(|(x, y)| (x - size / 2, y - size / 2))
+        .collect()
+}
+
+criterion_group!(benches, hashers_bench);
+criterion_main!(benches);
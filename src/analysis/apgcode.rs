@@ -0,0 +1,339 @@
+//! [apgcodes](https://conwaylife.com/wiki/Apgcode), the canonical object
+//! names used by apgsearch and Catagolue.
+//!
+//! An apgcode consists of a prefix with the object's kind (`xs` for still
+//! lifes, `xp` for oscillators, `xq` for spaceships) followed by its
+//! population or period, and the object's cells in the extended Wechsler
+//! format, e.g. `xs4_33` (block), `xp2_7` (blinker) or `xq4_153` (glider).
+//!
+//! Only two-state objects have apgcodes, so all non-empty cells are treated
+//! as alive.
+
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    analysis::{evolve, normalize, place, ObjectKind, Shape},
+    engine::{life_like::Grid, Coord, Pos},
+};
+
+/// Height of the strips the cells are split into, one bit per row.
+const STRIP_HEIGHT: u32 = 5;
+/// Digits of the extended Wechsler format, in the order used to pick the
+/// canonical code.
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+/// Longest run of empty columns that can be encoded with a single `y`.
+const MAX_ZERO_RUN: usize = 39;
+
+/// Computes the apgcode of a stabilized object by evolving it with `step`
+/// for up to `max_period` generations.
+///
+/// The code is canonical: it's the same for all phases, rotations and
+/// reflections of the object. Returns `None` if the object is empty or
+/// doesn't repeat itself within `max_period` generations.
+///
+/// See [`classify()`](crate::analysis::classify) for the requirements on
+/// the object.
+pub fn encode<S, F>(object: &Grid<S>, max_period: u32, step: F) -> Option<String>
+where
+    S: Clone + PartialEq,
+    F: FnMut(Grid<S>) -> Grid<S>,
+{
+    let (kind, phases) = evolve(object, max_period, step);
+
+    from_phases(kind, &phases)
+}
+
+/// Computes the apgcode of an object of the given kind from all of its
+/// phases.
+pub(crate) fn from_phases<S>(kind: ObjectKind, phases: &[Shape<S>]) -> Option<String> {
+    let prefix = match kind {
+        ObjectKind::StillLife => format!("xs{}", phases.first()?.len()),
+        ObjectKind::Oscillator { period } => format!("xp{period}"),
+        ObjectKind::Spaceship { period, .. } => format!("xq{period}"),
+        ObjectKind::Unknown => return None,
+    };
+    let code = phases
+        .iter()
+        .map(|phase| canonical_wechsler(phase.iter().map(|(pos, _)| *pos)))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))?;
+
+    Some(format!("{prefix}_{code}"))
+}
+
+/// Returns the canonical extended Wechsler code of a single phase of an
+/// object, i.e. the part of an apgcode after `_`.
+///
+/// Rotations and reflections of the object have the same code.
+pub fn wechsler<S: Clone>(object: &Grid<S>) -> String {
+    normalize(object)
+        .map(|(shape, _)| canonical_wechsler(shape.into_iter().map(|(pos, _)| pos)))
+        .unwrap_or_default()
+}
+
+/// Parses an apgcode of a still life, oscillator or spaceship.
+///
+/// The object's bounding box is placed at `(0, 0)`.
+///
+/// # Errors
+/// Returns an error if the prefix is unknown or the code contains a
+/// character that isn't allowed at its position.
+pub fn decode(code: &str) -> Result<Grid<()>, ApgcodeError> {
+    let invalid_prefix = || ApgcodeError::InvalidPrefix(code.to_owned());
+    let (prefix, cells) = code.split_once('_').ok_or_else(invalid_prefix)?;
+    let number = prefix
+        .strip_prefix("xs")
+        .or_else(|| prefix.strip_prefix("xp"))
+        .or_else(|| prefix.strip_prefix("xq"))
+        .ok_or_else(invalid_prefix)?;
+
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid_prefix());
+    }
+
+    let mut grid = Grid::default();
+    let (mut x, mut strip) = (0_usize, 0_usize);
+    let mut chars = cells.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            'w' => x = x.saturating_add(2),
+            'x' => x = x.saturating_add(3),
+            'y' => {
+                let run = chars
+                    .next()
+                    .ok_or(ApgcodeError::UnexpectedEnd)
+                    .and_then(|c| digit(c).ok_or(ApgcodeError::UnexpectedChar(c)))?;
+                x = x.saturating_add(run).saturating_add(4);
+            }
+            'z' => {
+                x = 0;
+                strip = strip.saturating_add(1);
+            }
+            c => {
+                let column = digit(c)
+                    .filter(|n| *n < 1 << STRIP_HEIGHT)
+                    .ok_or(ApgcodeError::UnexpectedChar(c))?;
+
+                for row in (0..STRIP_HEIGHT).filter(|row| column.wrapping_shr(*row) & 1 == 1) {
+                    let y = strip
+                        .saturating_mul(STRIP_HEIGHT as usize)
+                        .saturating_add(row as usize);
+                    grid.insert((0_i64.offset(x), 0_i64.offset(y)), ());
+                }
+
+                x = x.saturating_add(1);
+            }
+        }
+    }
+
+    Ok(normalize(&grid).map_or_else(Grid::default, |(shape, _)| place(&shape)))
+}
+
+/// An error encountered while decoding an apgcode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ApgcodeError {
+    /// The code doesn't start with `xs`, `xp` or `xq`, followed by a number
+    /// and `_`.
+    InvalidPrefix(String),
+    /// A character that isn't allowed at this position.
+    UnexpectedChar(char),
+    /// The code ended in the middle of a run of empty columns.
+    UnexpectedEnd,
+}
+
+impl fmt::Display for ApgcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPrefix(code) => write!(f, "invalid prefix in `{code}`"),
+            Self::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of code"),
+        }
+    }
+}
+
+impl std::error::Error for ApgcodeError {}
+
+/// Returns the shortest (and then lexicographically smallest) code among
+/// all orientations of the cells.
+pub(crate) fn canonical_wechsler<I: IntoIterator<Item = Pos>>(cells: I) -> String {
+    let cells: Vec<Pos> = cells.into_iter().collect();
+    let (width, height) = cells.iter().fold((0, 0), |(width, height), (x, y)| {
+        (
+            width.max(x.saturating_add(1)),
+            height.max(y.saturating_add(1)),
+        )
+    });
+
+    (0..8_u8)
+        .map(|orientation| {
+            let is_transposed = orientation & 4 != 0;
+            let (width, height) = if is_transposed {
+                (height, width)
+            } else {
+                (width, height)
+            };
+            let oriented = cells.iter().map(|(x, y)| {
+                let (x, y) = if is_transposed { (*y, *x) } else { (*x, *y) };
+                let x = if orientation & 1 == 0 {
+                    x
+                } else {
+                    width.wrapping_sub(x).wrapping_sub(1)
+                };
+                let y = if orientation & 2 == 0 {
+                    y
+                } else {
+                    height.wrapping_sub(y).wrapping_sub(1)
+                };
+
+                (x, y)
+            });
+
+            encode_cells(&oriented.collect(), width, height)
+        })
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        .unwrap_or_default()
+}
+
+/// Encodes cells in a `width` x `height` box in the extended Wechsler
+/// format.
+fn encode_cells(cells: &HashSet<Pos>, width: usize, height: usize) -> String {
+    let strips_n = height.div_ceil(STRIP_HEIGHT as usize);
+    let mut code = String::new();
+
+    for strip in 0..strips_n {
+        if strip > 0 {
+            code.push('z');
+        }
+
+        let top = strip.saturating_mul(STRIP_HEIGHT as usize);
+        let mut zeros = 0_usize;
+
+        for x in 0..width {
+            let column = (0..STRIP_HEIGHT)
+                .filter(|row| cells.contains(&(x, top.saturating_add(*row as usize))))
+                .fold(0_usize, |column, row| column | 1_usize.wrapping_shl(row));
+
+            if column == 0 {
+                zeros = zeros.saturating_add(1);
+                continue;
+            }
+
+            push_zeros(&mut code, zeros);
+            zeros = 0;
+            code.push(char::from(DIGITS.get(column).copied().unwrap_or(b'0')));
+        }
+    }
+
+    code
+}
+
+/// Writes a run of empty columns.
+fn push_zeros(code: &mut String, mut zeros: usize) {
+    while zeros > MAX_ZERO_RUN {
+        code.push_str("yz");
+        zeros = zeros.saturating_sub(MAX_ZERO_RUN);
+    }
+
+    match zeros {
+        0 => (),
+        1 => code.push('0'),
+        2 => code.push('w'),
+        3 => code.push('x'),
+        _ => {
+            code.push('y');
+            code.push(char::from(
+                DIGITS.get(zeros.saturating_sub(4)).copied().unwrap_or(b'0'),
+            ));
+        }
+    }
+}
+
+/// Returns the value of an extended Wechsler digit.
+fn digit(c: char) -> Option<usize> {
+    c.to_digit(36)
+        .filter(|_| !c.is_ascii_uppercase())
+        .and_then(|n| usize::try_from(n).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, wechsler, ApgcodeError};
+    use crate::{
+        analysis::{conway, grid},
+        engine::life_like::Grid,
+    };
+
+    #[test]
+    fn encoding() {
+        let block = grid(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let beehive = grid(&[(1, 0), (2, 0), (0, 1), (3, 1), (1, 2), (2, 2)]);
+        let blinker = grid(&[(0, 0), (1, 0), (2, 0)]);
+        let glider = grid(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+        let lwss = grid(&[
+            (1, 0),
+            (4, 0),
+            (0, 1),
+            (0, 2),
+            (4, 2),
+            (0, 3),
+            (1, 3),
+            (2, 3),
+            (3, 3),
+        ]);
+
+        assert_eq!(encode(&block, 4, conway).as_deref(), Some("xs4_33"));
+        assert_eq!(encode(&beehive, 4, conway).as_deref(), Some("xs6_696"));
+        assert_eq!(encode(&blinker, 4, conway).as_deref(), Some("xp2_7"));
+        assert_eq!(encode(&glider, 4, conway).as_deref(), Some("xq4_153"));
+        assert_eq!(encode(&lwss, 4, conway).as_deref(), Some("xq4_6frc"));
+        assert_eq!(encode(&Grid::default(), 4, conway), None);
+
+        // Two blocks far apart
+        let mut blocks = block.clone();
+        blocks.extend(block.keys().map(|(x, y)| ((x.saturating_add(44), *y), ())));
+        assert_eq!(wechsler(&blocks), "33yzx33");
+    }
+
+    #[test]
+    fn decoding() {
+        for code in ["xs4_33", "xs6_696", "xp2_7", "xq4_153", "xq4_6frc"] {
+            let object = decode(code).unwrap();
+
+            assert_eq!(encode(&object, 4, conway).as_deref(), Some(code));
+        }
+
+        assert_eq!(
+            decode("xs8_33yzx33").unwrap(),
+            grid(&[
+                (0, 0),
+                (1, 0),
+                (0, 1),
+                (1, 1),
+                (44, 0),
+                (45, 0),
+                (44, 1),
+                (45, 1)
+            ])
+        );
+        assert_eq!(
+            decode("xs4_3z3").unwrap(),
+            grid(&[(0, 0), (0, 1), (0, 5), (0, 6)])
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            decode("33"),
+            Err(ApgcodeError::InvalidPrefix("33".to_owned()))
+        );
+        assert_eq!(
+            decode("xs_33"),
+            Err(ApgcodeError::InvalidPrefix("xs_33".to_owned()))
+        );
+        assert_eq!(decode("xs4_3A"), Err(ApgcodeError::UnexpectedChar('A')));
+        assert_eq!(decode("xs4_3w"), Ok(grid(&[(0, 0), (0, 1)])));
+        assert_eq!(decode("xs4_3y"), Err(ApgcodeError::UnexpectedEnd));
+    }
+}
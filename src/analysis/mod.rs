@@ -0,0 +1,552 @@
+//! Analysis of stabilized patterns.
+//!
+//! A grid can be [split](split()) into separate objects, which are then
+//! [classified](classify()) by evolving them in isolation, and tallied in a
+//! [`Census`].
+//!
+//! Evolution is done by a `step` function, which computes the next
+//! generation of a grid. For automata built with the
+//! [`life_like`](crate::engine::life_like) engine,
+//! [`Automaton::census()`](crate::engine::life_like::Automaton::census) uses
+//! the automaton's own rule.
+//!
+//! # Examples
+//! ```rust
+//! use lifers::{
+//!     analysis::{ObjectKind, SplitOptions},
+//!     engine::life_like::{count_neighbors, AutomatonBuilder, Grid},
+//! };
+//!
+//! // A block and a blinker
+//! let cells: Grid<()> = [(0, 0), (1, 0), (0, 1), (1, 1), (5, 0), (5, 1), (5, 2)]
+//!     .into_iter()
+//!     .map(|pos| (pos, ()))
+//!     .collect();
+//! let game = AutomatonBuilder::new(1)
+//!     .init(|| cells.clone())
+//!     .map(|pos, _, cells| count_neighbors(pos, 1, cells))
+//!     .run(|_, state, neighbors_n| match state {
+//!         Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+//!         None => (neighbors_n == 3).then_some(()),
+//!     });
+//!
+//! let census = game.census(SplitOptions::default(), 8);
+//! let kinds: Vec<_> = census.entries().iter().map(|entry| entry.kind).collect();
+//!
+//! assert_eq!(kinds, [ObjectKind::StillLife, ObjectKind::Oscillator { period: 2 }]);
+//! ```
+
+pub mod apgcode;
+
+use std::collections::HashMap;
+
+use crate::engine::{life_like::Grid, Coord, Pos};
+
+/// Cells that are considered neighbors of each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Connectivity {
+    /// Cells sharing an edge (von Neumann neighborhood).
+    Orthogonal,
+    /// Cells sharing an edge or a corner (Moore neighborhood).
+    #[default]
+    Moore,
+}
+
+/// Settings for [`split()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SplitOptions {
+    pub connectivity: Connectivity,
+    /// Objects with cells at most this far apart (in both directions) are
+    /// merged into one. 0 and 1 don't merge anything beyond `connectivity`.
+    pub merge_distance: usize,
+}
+
+impl SplitOptions {
+    pub const fn new(connectivity: Connectivity, merge_distance: usize) -> Self {
+        Self {
+            connectivity,
+            merge_distance,
+        }
+    }
+
+    /// Returns `true` if cells at the given offset from each other belong
+    /// to the same object.
+    fn are_connected(&self, dx: usize, dy: usize) -> bool {
+        let is_neighbor = match self.connectivity {
+            Connectivity::Orthogonal => dx.saturating_add(dy) == 1,
+            Connectivity::Moore => dx.max(dy) == 1,
+        };
+
+        is_neighbor || (self.merge_distance > 1 && dx.max(dy) <= self.merge_distance)
+    }
+}
+
+/// What an object turned out to be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ObjectKind {
+    /// An object that doesn't change.
+    StillLife,
+    /// An object that returns to its initial state after `period`
+    /// generations.
+    Oscillator { period: u32 },
+    /// An object that returns to its initial state after `period`
+    /// generations, moved by `displacement`. Its velocity is
+    /// `displacement / period`.
+    Spaceship {
+        period: u32,
+        displacement: (i64, i64),
+    },
+    /// An object that didn't return to its initial state (or died out)
+    /// within the maximum period.
+    Unknown,
+}
+
+/// Cells of an object sorted by their positions, relative to the top-left
+/// corner of its bounding box.
+type Shape<S> = Vec<(Pos, S)>;
+
+/// Splits a grid into objects made of connected cells.
+///
+/// Objects keep their positions and are ordered by their top-most,
+/// left-most cells.
+pub fn split<S: Clone>(grid: &Grid<S>, options: SplitOptions) -> Vec<Grid<S>> {
+    let mut positions: Vec<Pos<i64>> = grid.keys().copied().collect();
+    positions.sort_unstable_by_key(|(x, y)| (*y, *x));
+
+    let indices: HashMap<Pos<i64>, usize> = positions
+        .iter()
+        .enumerate()
+        .map(|(idx, pos)| (*pos, idx))
+        .collect();
+    let mut parents: Vec<usize> = (0..positions.len()).collect();
+    let radius = i64::try_from(options.merge_distance.max(1)).unwrap_or(i64::MAX);
+
+    for (idx, (x, y)) in positions.iter().enumerate() {
+        // Only look forward, pairs are symmetric
+        for ny in *y..=y.saturating_add(radius) {
+            for nx in x.saturating_sub(radius)..=x.saturating_add(radius) {
+                let Some(other) = indices.get(&(nx, ny)) else {
+                    continue;
+                };
+
+                if *other > idx && options.are_connected(nx.distance(*x), ny.distance(*y)) {
+                    let (root, other_root) = (find(&mut parents, idx), find(&mut parents, *other));
+
+                    // Keep the earliest cell as the root, so objects stay
+                    // ordered
+                    if let Some(parent) = parents.get_mut(root.max(other_root)) {
+                        *parent = root.min(other_root);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut objects: Vec<Grid<S>> = Vec::new();
+    let mut object_indices = HashMap::new();
+
+    for (idx, pos) in positions.iter().enumerate() {
+        let root = find(&mut parents, idx);
+        let object_idx = *object_indices.entry(root).or_insert_with(|| {
+            objects.push(Grid::default());
+            objects.len().saturating_sub(1)
+        });
+
+        if let (Some(object), Some(state)) = (objects.get_mut(object_idx), grid.get(pos)) {
+            object.insert(*pos, state.clone());
+        }
+    }
+
+    objects
+}
+
+/// Classifies an object by evolving it with `step` for up to `max_period`
+/// generations.
+///
+/// The object should be isolated (see [`split()`]) and stabilized.
+pub fn classify<S, F>(object: &Grid<S>, max_period: u32, step: F) -> ObjectKind
+where
+    S: Clone + PartialEq,
+    F: FnMut(Grid<S>) -> Grid<S>,
+{
+    evolve(object, max_period, step).0
+}
+
+/// Evolves an object until it returns to its initial state, returning its
+/// kind and the shapes of all phases it went through.
+fn evolve<S, F>(object: &Grid<S>, max_period: u32, mut step: F) -> (ObjectKind, Vec<Shape<S>>)
+where
+    S: Clone + PartialEq,
+    F: FnMut(Grid<S>) -> Grid<S>,
+{
+    let Some((initial, (initial_x, initial_y))) = normalize(object) else {
+        return (ObjectKind::Unknown, Vec::new());
+    };
+    let mut grid = object.clone();
+    let mut phases = vec![initial];
+
+    for period in 1..=max_period {
+        grid = step(grid);
+
+        let Some((shape, (x, y))) = normalize(&grid) else {
+            break;
+        };
+
+        if phases.first() == Some(&shape) {
+            let displacement = (x.saturating_sub(initial_x), y.saturating_sub(initial_y));
+            let kind = match (displacement, period) {
+                ((0, 0), 1) => ObjectKind::StillLife,
+                ((0, 0), _) => ObjectKind::Oscillator { period },
+                _ => ObjectKind::Spaceship {
+                    period,
+                    displacement,
+                },
+            };
+
+            return (kind, phases);
+        }
+
+        phases.push(shape);
+    }
+
+    phases.truncate(1);
+
+    (ObjectKind::Unknown, phases)
+}
+
+/// A tally of distinct objects, similar to the ones reported by
+/// [apgsearch](https://conwaylife.com/wiki/Apgsearch).
+///
+/// Objects are considered the same if they have the same shape in any
+/// phase, regardless of their position and orientation. Like with
+/// [apgcodes](apgcode), all cells are treated as alive when comparing
+/// shapes.
+#[derive(Debug, Clone)]
+pub struct Census<S> {
+    entries: Vec<CensusEntry<S>>,
+    /// Indices of entries, by the canonical Wechsler codes (see
+    /// [`apgcode::wechsler()`]) of all phases of their objects.
+    index: HashMap<String, usize>,
+}
+
+/// A distinct object in a [`Census`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CensusEntry<S> {
+    /// The object in the phase it was first seen in, with its bounding box
+    /// at `(0, 0)`.
+    pub object: Grid<S>,
+    pub kind: ObjectKind,
+    /// The object's [apgcode], unless its kind is
+    /// [`ObjectKind::Unknown`].
+    pub apgcode: Option<String>,
+    /// Number of times the object was seen.
+    pub count: usize,
+}
+
+impl<S> Default for Census<S> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+}
+
+impl<S: Clone + PartialEq> Census<S> {
+    /// Splits a grid into objects and tallies them.
+    ///
+    /// See [`split()`] and [`classify()`].
+    pub fn take<F>(grid: &Grid<S>, options: SplitOptions, max_period: u32, step: F) -> Self
+    where
+        F: FnMut(Grid<S>) -> Grid<S>,
+    {
+        let mut census = Self::default();
+        census.add(grid, options, max_period, step);
+
+        census
+    }
+
+    /// Splits a grid into objects and adds them to the tally.
+    pub fn add<F>(&mut self, grid: &Grid<S>, options: SplitOptions, max_period: u32, mut step: F)
+    where
+        F: FnMut(Grid<S>) -> Grid<S>,
+    {
+        for object in split(grid, options) {
+            let Some((shape, _)) = normalize(&object) else {
+                continue;
+            };
+            let code = shape_code(&shape);
+
+            if let Some(entry) = self
+                .index
+                .get(&code)
+                .and_then(|idx| self.entries.get_mut(*idx))
+            {
+                entry.count = entry.count.saturating_add(1);
+                continue;
+            }
+
+            let (kind, phases) = evolve(&object, max_period, &mut step);
+            let idx = self.entries.len();
+
+            self.entries.push(CensusEntry {
+                object: place(&shape),
+                kind,
+                apgcode: apgcode::from_phases(kind, &phases),
+                count: 1,
+            });
+            self.index.insert(code, idx);
+
+            for phase in &phases {
+                self.index.entry(shape_code(phase)).or_insert(idx);
+            }
+        }
+    }
+
+    /// Returns the number of times an object (in any phase, position and
+    /// orientation) was seen.
+    pub fn count(&self, object: &Grid<S>) -> usize {
+        normalize(object)
+            .and_then(|(shape, _)| self.index.get(&shape_code(&shape)))
+            .and_then(|idx| self.entries.get(*idx))
+            .map_or(0, |entry| entry.count)
+    }
+}
+
+impl<S> Census<S> {
+    /// Returns the distinct objects in the order they were first seen.
+    pub fn entries(&self) -> &[CensusEntry<S>] {
+        &self.entries
+    }
+
+    /// Returns the distinct objects, most common first.
+    pub fn sorted_entries(&self) -> Vec<&CensusEntry<S>> {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.count));
+
+        entries
+    }
+
+    /// Returns the total number of objects seen.
+    pub fn total(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+}
+
+/// Returns the shape of an object along with the top-left corner of its
+/// bounding box, or `None` if it's empty.
+fn normalize<S: Clone>(object: &Grid<S>) -> Option<(Shape<S>, Pos<i64>)> {
+    let (min_x, min_y) = object
+        .keys()
+        .copied()
+        .reduce(|(min_x, min_y), (x, y)| (min_x.min(x), min_y.min(y)))?;
+    let mut shape: Shape<S> = object
+        .iter()
+        .map(|((x, y), state)| ((x.distance(min_x), y.distance(min_y)), state.clone()))
+        .collect();
+    shape.sort_unstable_by_key(|(pos, _)| *pos);
+
+    Some((shape, (min_x, min_y)))
+}
+
+/// Returns the canonical Wechsler code of a shape, which is the same for all
+/// of its orientations.
+fn shape_code<S>(shape: &[(Pos, S)]) -> String {
+    apgcode::canonical_wechsler(shape.iter().map(|(pos, _)| *pos))
+}
+
+/// Returns the cells of a shape with the top-left corner of its bounding box
+/// at `(0, 0)`.
+fn place<S: Clone>(shape: &[(Pos, S)]) -> Grid<S> {
+    shape
+        .iter()
+        .map(|((x, y), state)| ((0_i64.offset(*x), 0_i64.offset(*y)), state.clone()))
+        .collect()
+}
+
+/// Finds the root of a cell's set, compressing the path to it.
+fn find(parents: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+
+    while let Some(parent) = parents.get(root).copied().filter(|parent| *parent != root) {
+        root = parent;
+    }
+
+    let mut current = idx;
+
+    while let Some(parent) = parents.get_mut(current) {
+        let next = *parent;
+        *parent = root;
+
+        if next == current {
+            break;
+        }
+
+        current = next;
+    }
+
+    root
+}
+
+/// Returns a grid with the given live cells, shared by the tests of this
+/// module and its submodules.
+#[cfg(test)]
+pub(crate) fn grid(cells: &[Pos<i64>]) -> Grid<()> {
+    cells.iter().map(|pos| (*pos, ())).collect()
+}
+
+/// Computes the next generation of Conway's Game of Life, shared by the
+/// tests of this module and its submodules.
+#[cfg(test)]
+pub(crate) fn conway(cells: Grid<()>) -> Grid<()> {
+    use crate::engine::life_like::{count_neighbors, Automaton};
+
+    let mut game = Automaton::new(
+        cells,
+        1,
+        None,
+        |_, state, neighbors_n| match state {
+            Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+            None => (neighbors_n == 3).then_some(()),
+        },
+        |pos, _, cells| count_neighbors(pos, 1, cells),
+    );
+    game.step();
+
+    game.cells().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, conway, grid, split, Census, Connectivity, ObjectKind, SplitOptions};
+
+    #[test]
+    fn splitting() {
+        // Two cells touching at a corner, and one two cells away
+        let cells = grid(&[(0, 0), (1, 1), (3, 1)]);
+
+        assert_eq!(split(&cells, SplitOptions::default()).len(), 2);
+        assert_eq!(
+            split(&cells, SplitOptions::new(Connectivity::Orthogonal, 0)).len(),
+            3
+        );
+
+        let merged = split(&cells, SplitOptions::new(Connectivity::Moore, 2));
+        assert_eq!(merged, [cells]);
+
+        // A merge distance of 1 doesn't connect diagonal cells
+        let diagonal = grid(&[(0, 0), (1, 1)]);
+        assert_eq!(
+            split(&diagonal, SplitOptions::new(Connectivity::Orthogonal, 1)).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn classification() {
+        let block = grid(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let blinker = grid(&[(1, 0), (1, 1), (1, 2)]);
+        // Moving up and to the left
+        let glider = grid(&[(0, 0), (1, 0), (2, 0), (0, 1), (1, 2)]);
+        let r_pentomino = grid(&[(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)]);
+
+        assert_eq!(classify(&block, 4, conway), ObjectKind::StillLife);
+        assert_eq!(
+            classify(&blinker, 4, conway),
+            ObjectKind::Oscillator { period: 2 }
+        );
+        assert_eq!(
+            classify(&glider, 4, conway),
+            ObjectKind::Spaceship {
+                period: 4,
+                displacement: (-1, -1)
+            }
+        );
+        assert_eq!(classify(&r_pentomino, 8, conway), ObjectKind::Unknown);
+    }
+
+    #[test]
+    fn census() {
+        let cells = grid(&[
+            // Blinkers in both phases
+            (1, 0),
+            (1, 1),
+            (1, 2),
+            (10, 1),
+            (11, 1),
+            (12, 1),
+            // Blocks
+            (0, 10),
+            (1, 10),
+            (0, 11),
+            (1, 11),
+            (10, 10),
+            (11, 10),
+            (10, 11),
+            (11, 11),
+            (20, 20),
+            (21, 20),
+            (20, 21),
+            (21, 21),
+        ]);
+        let census = Census::take(&cells, SplitOptions::default(), 4, conway);
+        let counts: Vec<_> = census
+            .sorted_entries()
+            .into_iter()
+            .map(|entry| (entry.kind, entry.count))
+            .collect();
+
+        assert_eq!(census.total(), 5);
+        assert_eq!(
+            counts,
+            [
+                (ObjectKind::StillLife, 3),
+                (ObjectKind::Oscillator { period: 2 }, 2)
+            ]
+        );
+        assert_eq!(census.count(&grid(&[(5, 5), (6, 5), (7, 5)])), 2);
+        assert_eq!(
+            census
+                .entries()
+                .iter()
+                .map(|entry| entry.apgcode.as_deref())
+                .collect::<Vec<_>>(),
+            [Some("xp2_7"), Some("xs4_33")]
+        );
+    }
+
+    #[test]
+    fn census_orientations() {
+        // Gliders moving up-left and up-right
+        let cells = grid(&[
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (0, 1),
+            (1, 2),
+            (10, 0),
+            (11, 0),
+            (12, 0),
+            (12, 1),
+            (11, 2),
+        ]);
+        let census = Census::take(&cells, SplitOptions::default(), 4, conway);
+
+        assert_eq!(census.entries().len(), 1);
+        assert_eq!(census.total(), 2);
+        assert_eq!(
+            census
+                .entries()
+                .first()
+                .and_then(|entry| entry.apgcode.as_deref()),
+            Some("xq4_153")
+        );
+        // Another phase in yet another orientation
+        assert_eq!(
+            census.count(&grid(&[(0, 0), (2, 0), (1, 1), (2, 1), (1, 2)])),
+            2
+        );
+    }
+}
@@ -0,0 +1,558 @@
+//! An automaton that handles (potentially) infinitely-sized grids filled
+//! with a background state.
+//!
+//! Only the cells whose state differs from the background are stored, which
+//! allows dead cells to carry data (e.g. an ambient temperature or a
+//! pheromone level), unlike in the [`life_like`] engine.
+//!
+//! The [`life_like`] engine is the special case of `Option<S>` states with a
+//! background of `None`, see the [`From`] implementation of [`Grid`].
+//!
+//! The background state itself changes too: every generation, it becomes
+//! the next state of a background cell surrounded by background cells. This
+//! makes it possible to run rules like the ones with B0, where a dead cell
+//! with no live neighbors comes alive (see [`totalistic`]).
+//!
+//! # Limitations
+//! All cells that aren't in a radius of a stored cell evolve the same way.
+//! The next background state is computed for the origin of an otherwise
+//! empty grid, so the step and data functions shouldn't depend on the
+//! position of such cells.
+//!
+//! History, cycle detection, statistics and observers aren't supported.
+//!
+//! # Examples
+//! ```rust
+//! use lifers::engine::background::{AutomatonBuilder, Grid};
+//!
+//! // A pheromone trail that fades out to the ambient level of 1
+//! let mut game = AutomatonBuilder::new(1)
+//!     .init(|| Grid::from_cells(1_u8, [((0, 0), 4)]))
+//!     .map(|pos, _, cells| {
+//!         lifers::engine::background::neighbors(pos, 1, cells)
+//!             .copied()
+//!             .max()
+//!             .unwrap_or_default()
+//!     })
+//!     .run(|_, level, max_neighbor| level.max(max_neighbor).saturating_sub(1).max(1));
+//!
+//! game.step();
+//! assert_eq!(game.cells().len(), 9);
+//! assert_eq!(*game.get((5, 5)), 1);
+//! ```
+//!
+//! [`life_like`]: super::life_like
+//! [`totalistic`]: super::totalistic
+
+use rustc_hash::FxBuildHasher;
+use std::{collections::HashMap, fmt, hash::BuildHasher};
+
+use crate::{
+    engine::{self, life_like, ExecutionState, Pos, Rect},
+    impl_builder_misc,
+};
+
+pub type DataFn<S, D, H = FxBuildHasher> = fn(Pos<i64>, &S, &Grid<S, H>) -> D;
+pub type StepFn<S, D> = fn(Pos<i64>, S, D) -> S;
+
+/// A sparse grid where all cells that aren't stored are in the background
+/// state.
+#[derive(Clone)]
+pub struct Grid<S, H = FxBuildHasher> {
+    cells: HashMap<Pos<i64>, S, H>,
+    background: S,
+}
+
+impl<S> Grid<S> {
+    /// Creates a grid with all cells in the `background` state.
+    pub const fn new(background: S) -> Self {
+        Self::with_hasher(background, FxBuildHasher)
+    }
+}
+
+impl<S: PartialEq> Grid<S> {
+    /// Creates a grid with the given cells, leaving out the ones in the
+    /// `background` state.
+    pub fn from_cells<I: IntoIterator<Item = (Pos<i64>, S)>>(background: S, cells: I) -> Self {
+        let mut grid = Self::new(background);
+        grid.extend(cells);
+
+        grid
+    }
+}
+
+impl<S, H> Grid<S, H> {
+    /// Like [`new()`](Self::new), but with a custom hasher (see
+    /// [`hasher`](super::hasher)).
+    pub const fn with_hasher(background: S, hasher: H) -> Self {
+        Self {
+            cells: HashMap::with_hasher(hasher),
+            background,
+        }
+    }
+
+    pub const fn background(&self) -> &S {
+        &self.background
+    }
+
+    /// Returns the stored cells, i.e. the ones that aren't in the background
+    /// state.
+    pub const fn cells(&self) -> &HashMap<Pos<i64>, S, H> {
+        &self.cells
+    }
+
+    /// Returns the number of stored cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `true` if all cells are in the background state.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns an iterator over the stored cells.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos<i64>, &S)> {
+        self.cells.iter().map(|(pos, state)| (*pos, state))
+    }
+
+    /// Resets all cells to the background state.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+}
+
+impl<S: PartialEq, H: BuildHasher> Grid<S, H> {
+    /// Returns the state of the cell at `pos`.
+    pub fn get(&self, pos: Pos<i64>) -> &S {
+        self.cells.get(&pos).unwrap_or(&self.background)
+    }
+
+    /// Sets the state of the cell at `pos`, returning the previous one if it
+    /// wasn't the background state.
+    pub fn set(&mut self, pos: Pos<i64>, state: S) -> Option<S> {
+        if state == self.background {
+            self.cells.remove(&pos)
+        } else {
+            self.cells.insert(pos, state)
+        }
+    }
+}
+
+impl<S: PartialEq, H: BuildHasher> Extend<(Pos<i64>, S)> for Grid<S, H> {
+    fn extend<I: IntoIterator<Item = (Pos<i64>, S)>>(&mut self, cells: I) {
+        for (pos, state) in cells {
+            self.set(pos, state);
+        }
+    }
+}
+
+/// Stores live cells as `Some` on a background of `None`.
+impl<S, H: BuildHasher + Default> From<life_like::Grid<S, H>> for Grid<Option<S>, H> {
+    fn from(grid: life_like::Grid<S, H>) -> Self {
+        Self {
+            cells: grid
+                .into_iter()
+                .map(|(pos, state)| (pos, Some(state)))
+                .collect(),
+            background: None,
+        }
+    }
+}
+
+// NOTE: Implemented manually, since hashers usually don't implement `Debug`
+// and `PartialEq`.
+impl<S: fmt::Debug, H> fmt::Debug for Grid<S, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Grid")
+            .field("cells", &self.cells)
+            .field("background", &self.background)
+            .finish()
+    }
+}
+
+impl<S: PartialEq, H: BuildHasher> PartialEq for Grid<S, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.background == other.background && self.cells == other.cells
+    }
+}
+
+impl<S: Eq, H: BuildHasher> Eq for Grid<S, H> {}
+
+/// A Life-like automaton on a grid with a background state.
+///
+/// See the [module-level documentation](self).
+// NOTE: This isn't the base of the `life_like` engine, since storing
+// `Option<S>` values and comparing them with the background would cost it
+// memory and speed, and its grid type is part of its API. The neighborhood
+// and the cells evaluated in a step are shared with it instead.
+pub struct Automaton<S, D = (), H = FxBuildHasher> {
+    cells: Grid<S, H>,
+    radius: u8,
+    /// Number of generations computed so far.
+    generation: u64,
+    generations_left: Option<u32>,
+    /// `None` if the automaton is only stepped with
+    /// [`step_with()`](Self::step_with).
+    step_fn: Option<StepFn<S, D>>,
+    data_fn: DataFn<S, D, H>,
+}
+
+impl<S, D, H> Automaton<S, D, H> {
+    pub const fn new(
+        cells: Grid<S, H>,
+        radius: u8,
+        generations_left: Option<u32>,
+        step_fn: StepFn<S, D>,
+        data_fn: DataFn<S, D, H>,
+    ) -> Self {
+        Self {
+            cells,
+            radius,
+            generation: 0,
+            generations_left,
+            step_fn: Some(step_fn),
+            data_fn,
+        }
+    }
+
+    /// Like [`new()`](Self::new), but for automata whose step function
+    /// isn't a plain `fn` and is passed to [`step_with()`](Self::step_with)
+    /// instead. [`step()`](Self::step) must not be called on them.
+    pub(crate) const fn without_step_fn(
+        cells: Grid<S, H>,
+        radius: u8,
+        generations_left: Option<u32>,
+        data_fn: DataFn<S, D, H>,
+    ) -> Self {
+        Self {
+            cells,
+            radius,
+            generation: 0,
+            generations_left,
+            step_fn: None,
+            data_fn,
+        }
+    }
+
+    pub const fn cells(&self) -> &Grid<S, H> {
+        &self.cells
+    }
+
+    pub const fn cells_mut(&mut self) -> &mut Grid<S, H> {
+        &mut self.cells
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.generations_left == Some(0)
+    }
+
+    /// Returns the number of generations computed so far.
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl<S: Clone + PartialEq, D, H: BuildHasher + Default> Automaton<S, D, H> {
+    /// Computes the next generation.
+    ///
+    /// Stored cells and the background cells around them are evaluated, all
+    /// other cells take the next background state.
+    pub fn step(&mut self) -> ExecutionState {
+        // NOTE: Automata without a step function are private to the engines
+        // wrapping them, which only use `step_with()`
+        let Some(step_fn) = self.step_fn else {
+            unreachable!()
+        };
+
+        self.step_with(step_fn)
+    }
+
+    /// Like [`step()`](Self::step), but with a step function that can
+    /// capture its environment (e.g. a rule that's only known at runtime).
+    pub(crate) fn step_with<F>(&mut self, mut step_fn: F) -> ExecutionState
+    where
+        F: FnMut(Pos<i64>, S, D) -> S,
+    {
+        let background_cells = life_like::surrounding_cells(&self.cells.cells, self.radius);
+        let background = &self.cells.background;
+        let next_background = {
+            let empty = Grid::with_hasher(background.clone(), H::default());
+            let data = (self.data_fn)((0, 0), background, &empty);
+
+            step_fn((0, 0), background.clone(), data)
+        };
+        let next: Vec<_> = self
+            .cells
+            .iter()
+            .chain(background_cells.into_iter().map(|pos| (pos, background)))
+            .map(|(pos, state)| {
+                let data = (self.data_fn)(pos, state, &self.cells);
+
+                (pos, step_fn(pos, state.clone(), data))
+            })
+            .collect();
+
+        self.cells.clear();
+        self.cells.background = next_background;
+        self.cells.extend(next);
+        self.generation = self.generation.saturating_add(1);
+
+        self.generations_left.map_or(ExecutionState::Infinite, |x| {
+            x.checked_sub(1).map_or(ExecutionState::Finished, |y| {
+                self.generations_left = Some(y);
+                ExecutionState::Remaining(y)
+            })
+        })
+    }
+
+    /// Computes up to `generations` generations, stopping early if the
+    /// generation limit is reached.
+    ///
+    /// Returns the state returned by the last step.
+    pub fn run_for(&mut self, generations: u32) -> ExecutionState {
+        let mut state = ExecutionState::from_generations_left(self.generations_left);
+
+        for _ in 0..generations {
+            if self.is_finished() {
+                break;
+            }
+
+            state = self.step();
+        }
+
+        state
+    }
+}
+
+impl<S: PartialEq, D, H: BuildHasher> Automaton<S, D, H> {
+    /// Returns the state of the cell at `pos`.
+    pub fn get(&self, pos: Pos<i64>) -> &S {
+        self.cells.get(pos)
+    }
+
+    /// Sets the state of the cell at `pos`, returning the previous one if it
+    /// wasn't the background state.
+    pub fn set(&mut self, pos: Pos<i64>, state: S) -> Option<S> {
+        self.cells.set(pos, state)
+    }
+}
+
+impl<S: Clone + PartialEq, D, H: BuildHasher + Default> engine::Automaton for Automaton<S, D, H> {
+    type State = S;
+    type Coord = i64;
+
+    fn step(&mut self) -> ExecutionState {
+        self.step()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.is_finished()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn generations_left(&self) -> Option<u32> {
+        self.generations_left
+    }
+
+    /// Returns the state of the cell at `pos`, which is never `None`.
+    fn get(&self, pos: Pos<i64>) -> Option<&S> {
+        Some(self.get(pos))
+    }
+
+    /// Returns an iterator over the cells that aren't in the background
+    /// state.
+    fn iter_cells(&self) -> impl Iterator<Item = (Pos<i64>, &S)> {
+        self.cells.iter()
+    }
+
+    /// Returns the bounding box of the cells that aren't in the background
+    /// state.
+    fn bounding_box(&self) -> Option<Rect<i64>> {
+        Rect::from_positions(self.cells.cells.keys().copied())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AutomatonBuilder {
+    radius: u8,
+}
+
+impl AutomatonBuilder {
+    pub const fn new(radius: u8) -> Self {
+        Self { radius }
+    }
+
+    pub fn init<F, S, H>(self, mut init_fn: F) -> InitBuilder<S, H>
+    where
+        F: FnMut() -> Grid<S, H>,
+    {
+        InitBuilder {
+            radius: self.radius,
+            cells: init_fn(),
+            generations_limit: None,
+        }
+    }
+}
+
+pub struct InitBuilder<S, H = FxBuildHasher> {
+    radius: u8,
+    cells: Grid<S, H>,
+    generations_limit: Option<u32>,
+}
+
+impl<S, H> InitBuilder<S, H> {
+    pub fn run(self, step_fn: StepFn<S, ()>) -> Automaton<S, (), H> {
+        Automaton::new(
+            self.cells,
+            self.radius,
+            self.generations_limit,
+            step_fn,
+            |_, _, _| (),
+        )
+    }
+
+    pub fn map<D>(self, data_fn: DataFn<S, D, H>) -> MappedBuilder<S, D, H> {
+        MappedBuilder {
+            radius: self.radius,
+            cells: self.cells,
+            data_fn,
+            generations_limit: self.generations_limit,
+        }
+    }
+
+    impl_builder_misc! { Self }
+}
+
+pub struct MappedBuilder<S, D, H = FxBuildHasher> {
+    radius: u8,
+    cells: Grid<S, H>,
+    data_fn: DataFn<S, D, H>,
+    generations_limit: Option<u32>,
+}
+
+impl<S, D, H> MappedBuilder<S, D, H> {
+    pub fn run(self, step_fn: StepFn<S, D>) -> Automaton<S, D, H> {
+        Automaton::new(
+            self.cells,
+            self.radius,
+            self.generations_limit,
+            step_fn,
+            self.data_fn,
+        )
+    }
+
+    impl_builder_misc! { Self }
+}
+
+/// Returns an iterator over the states of all neighbors in `radius`,
+/// including the ones in the background state.
+pub fn neighbors<S: PartialEq, H: BuildHasher>(
+    pos: Pos<i64>,
+    radius: u8,
+    grid: &Grid<S, H>,
+) -> impl Iterator<Item = &S> {
+    life_like::neighborhood(pos, radius).map(|pos| grid.get(pos))
+}
+
+/// Counts the neighbors in `radius` for which `is_alive` returns `true`.
+pub fn count_neighbors<S, H, F>(pos: Pos<i64>, radius: u8, grid: &Grid<S, H>, is_alive: F) -> u32
+where
+    S: PartialEq,
+    H: BuildHasher,
+    F: Fn(&S) -> bool,
+{
+    neighbors(pos, radius, grid)
+        .map(|state| u32::from(is_alive(state)))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_neighbors, neighbors, AutomatonBuilder, Grid};
+    use crate::engine::life_like;
+
+    #[test]
+    fn grid() {
+        let mut grid = Grid::from_cells(0_u8, [((0, 0), 1), ((1, 0), 0), ((-1, 2), 2)]);
+
+        assert_eq!(grid.len(), 2);
+        assert_eq!(*grid.get((1, 0)), 0);
+        assert_eq!(*grid.get((-1, 2)), 2);
+        assert_eq!(grid.set((0, 0), 0), Some(1));
+        assert_eq!(grid.set((5, 5), 0), None);
+        assert_eq!(grid.len(), 1);
+    }
+
+    #[test]
+    fn background_state() {
+        // Levels spread to the neighbors and fade out to the ambient level
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| Grid::from_cells(1_u8, [((0, 0), 4)]))
+            .map(|pos, _, cells| neighbors(pos, 1, cells).copied().max().unwrap_or_default())
+            .generations(5)
+            .run(|_, level, max_neighbor| level.max(max_neighbor).saturating_sub(1).max(1));
+
+        game.step();
+        assert_eq!(game.cells().len(), 9);
+        assert_eq!(*game.get((-1, -1)), 3);
+
+        game.step();
+        assert_eq!(game.cells().len(), 25);
+        assert_eq!(*game.get((2, -2)), 2);
+
+        game.step();
+        assert!(game.cells().is_empty());
+        assert_eq!(*game.get((0, 0)), 1);
+        assert_eq!(game.generation(), 3);
+    }
+
+    #[test]
+    fn life_like_equivalence() {
+        let r_pentomino: life_like::Grid<()> = [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)]
+            .into_iter()
+            .map(|pos| (pos, ()))
+            .collect();
+        let mut sparse = life_like::AutomatonBuilder::new(1)
+            .init(|| r_pentomino.clone())
+            .map(|pos, _, cells| life_like::count_neighbors(pos, 1, cells))
+            .run(|_, state, neighbors_n| match state {
+                Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+                None => (neighbors_n == 3).then_some(()),
+            });
+        let mut background = AutomatonBuilder::new(1)
+            .init(|| Grid::from(r_pentomino.clone()))
+            .map(|pos, _, cells| count_neighbors(pos, 1, cells, Option::is_some))
+            .run(|_, state, neighbors_n| match state {
+                Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+                None => (neighbors_n == 3).then_some(()),
+            });
+
+        sparse.run_for(20);
+        background.run_for(20);
+
+        assert_eq!(background.cells(), &Grid::from(sparse.cells().clone()));
+    }
+
+    #[test]
+    fn changing_background() {
+        // Every cell flips its state, including the background
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| Grid::from_cells(false, [((0, 0), true)]))
+            .run(|_, state, ()| !state);
+
+        game.step();
+        assert!(*game.cells().background());
+        assert_eq!(game.cells().len(), 1);
+        assert!(!*game.get((0, 0)));
+        assert!(*game.get((5, 5)));
+
+        game.step();
+        assert!(!*game.cells().background());
+        assert!(*game.get((0, 0)));
+    }
+}
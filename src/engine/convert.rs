@@ -0,0 +1,133 @@
+//! Conversion between dense ([`generic`]) and sparse ([`life_like`]) grids.
+//!
+//! Dense grids store every cell, while sparse grids only store live ones, so
+//! conversions take a function that decides which cells are dead and maps the
+//! states of the others (e.g. from `bool` to `()`). Whole automata can be
+//! converted with [`generic::Automaton::to_life_like()`] and
+//! [`life_like::Automaton::to_generic()`].
+
+use std::hash::BuildHasher;
+
+use crate::engine::{generic, life_like, Coord, Pos, Rect};
+
+/// Converts a dense grid into a sparse one.
+///
+/// `convert` maps the state of each cell, returning `None` for dead cells,
+/// which are dropped. Cells are moved by `offset`.
+pub fn to_sparse<S, T, F>(
+    grid: &generic::Grid<S>,
+    offset: Pos<i64>,
+    convert: F,
+) -> life_like::Grid<T>
+where
+    F: Fn(&S) -> Option<T>,
+{
+    generic::iter_grid(grid)
+        .filter_map(|(x, y, state)| {
+            convert(state).map(|state| ((offset.0.offset(x), offset.1.offset(y)), state))
+        })
+        .collect()
+}
+
+/// Converts the part of a sparse grid inside of `area` into a dense grid.
+///
+/// `convert` maps the state of each cell, receiving `None` for dead ones.
+/// Cells are moved by `(-area.x, -area.y)`. To convert the whole grid, use
+/// its bounding box (see [`Rect::from_positions()`]).
+#[allow(
+    clippy::implicit_hasher,
+    reason = "False positive, `Grid` is already generic over its hasher"
+)]
+pub fn to_dense<S, T, H, F>(
+    grid: &life_like::Grid<S, H>,
+    area: Rect<i64>,
+    convert: F,
+) -> generic::Grid<T>
+where
+    H: BuildHasher,
+    F: Fn(Option<&S>) -> T,
+{
+    (0..area.height)
+        .map(|dy| {
+            (0..area.width)
+                .map(|dx| convert(grid.get(&(area.x.offset(dx), area.y.offset(dy)))))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_dense, to_sparse};
+    use crate::engine::{
+        generic::{self, AutomatonBuilder},
+        life_like::{self, count_neighbors},
+        Rect,
+    };
+
+    #[test]
+    fn grids() {
+        let dense = vec![vec![0_u8, 1, 0], vec![0, 0, 2]];
+        let sparse = to_sparse(&dense, (10, 5), |state| (*state != 0).then_some(*state));
+        let to_dense_states = |state: Option<&u8>| state.copied().unwrap_or_default();
+
+        assert_eq!(sparse, [((11, 5), 1), ((12, 6), 2)].into_iter().collect());
+        assert_eq!(
+            to_dense(&sparse, Rect::new((10, 5), (3, 2)), to_dense_states),
+            dense
+        );
+        assert_eq!(
+            to_dense(&sparse, Rect::new((12, 4), (2, 2)), to_dense_states),
+            [[0, 0], [0, 0]]
+        );
+    }
+
+    #[test]
+    fn automata() {
+        let mut dense = AutomatonBuilder::new((5, 5))
+            .init(|(x, y)| x == 2 && (1..=3).contains(&y))
+            .map(|pos, _, cells| generic::count_neighbors(cells, pos, 1, |b| *b))
+            .generations(10)
+            .run(|_, is_alive, neighbors_n| {
+                if is_alive {
+                    (2..=3).contains(&neighbors_n)
+                } else {
+                    neighbors_n == 3
+                }
+            });
+
+        dense.step();
+
+        let mut sparse = dense.to_life_like(
+            (100, 100),
+            |is_alive| is_alive.then_some(()),
+            1,
+            |_, state, neighbors_n| match state {
+                Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+                None => (neighbors_n == 3).then_some(()),
+            },
+            |pos, _, cells| count_neighbors(pos, 1, cells),
+        );
+
+        assert_eq!(sparse.generation(), 1);
+        assert_eq!(
+            *sparse.cells(),
+            [((101, 102), ()), ((102, 102), ()), ((103, 102), ())]
+                .into_iter()
+                .collect::<life_like::Grid<()>>()
+        );
+
+        sparse.step();
+
+        let back = sparse.to_generic(
+            Rect::new((100, 100), (5, 5)),
+            |state| state.is_some(),
+            |_, is_alive, ()| is_alive,
+            |_, _, _| (),
+        );
+
+        assert_eq!(back.generation(), 2);
+        assert_eq!(back.snapshot().generations_left, Some(8));
+        assert_eq!(back.cells()[1], [false, false, true, false, false]);
+    }
+}
@@ -0,0 +1,85 @@
+//! Detection of static and periodic automata.
+//!
+//! Each generation is reduced to a hash, and the automaton is considered
+//! periodic once a hash repeats one of the last few generations'. Hash
+//! collisions are possible in theory, but very unlikely with 64-bit hashes.
+
+use std::collections::VecDeque;
+
+/// Remembers the hashes of the last generations.
+pub struct CycleDetector<G> {
+    /// Longest period that can be detected.
+    max_period: u32,
+    /// Hashes of the last `max_period` generations, newest last.
+    hashes: VecDeque<u64>,
+    hash_fn: fn(&G) -> u64,
+}
+
+impl<G> CycleDetector<G> {
+    pub fn new(max_period: u32, hash_fn: fn(&G) -> u64) -> Self {
+        Self {
+            max_period,
+            hashes: VecDeque::new(),
+            hash_fn,
+        }
+    }
+
+    pub const fn max_period(&self) -> u32 {
+        self.max_period
+    }
+
+    /// Records the generation computed before any other one, unless
+    /// something is already recorded.
+    pub fn seed(&mut self, grid: &G) {
+        if self.hashes.is_empty() {
+            self.hashes.push_back((self.hash_fn)(grid));
+        }
+    }
+
+    /// Records a generation, returning its period if it repeats one of the
+    /// last `max_period` generations.
+    pub fn record(&mut self, grid: &G) -> Option<u32> {
+        let hash = (self.hash_fn)(grid);
+        let period = self
+            .hashes
+            .iter()
+            .rev()
+            .position(|other| *other == hash)
+            .and_then(|idx| u32::try_from(idx.saturating_add(1)).ok());
+
+        self.hashes.push_back(hash);
+
+        if self.hashes.len() > usize::try_from(self.max_period).unwrap_or(usize::MAX) {
+            self.hashes.pop_front();
+        }
+
+        period
+    }
+
+    /// Forgets all recorded generations, e.g. after the grid was edited.
+    pub fn reset(&mut self) {
+        self.hashes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CycleDetector;
+
+    #[test]
+    fn periods() {
+        let mut detector = CycleDetector::new(3, |n: &u64| *n);
+
+        detector.seed(&0);
+        assert_eq!(detector.record(&1), None);
+        assert_eq!(detector.record(&2), None);
+        assert_eq!(detector.record(&0), Some(3));
+        assert_eq!(detector.record(&5), None);
+        // Too far back
+        assert_eq!(detector.record(&1), None);
+        assert_eq!(detector.record(&1), Some(1));
+
+        detector.reset();
+        assert_eq!(detector.record(&1), None);
+    }
+}
@@ -1,24 +1,50 @@
 //! Automata engine components for generics automatons. It handles
-//! scenes with a fixed grid and number of cells.
+//! scenes with a finite grid of cells.
+//!
+//! The grid can be resized at runtime (see [`Automaton::resize()`],
+//! [`Automaton::pad()`] and [`Automaton::crop()`]), or made to grow
+//! automatically with [`InitBuilder::grow_on_edge()`].
 //!
 //! # Performance
 //! This automaton has `O(n)` time complexity, where `n` is the **number
 //! of cells** _(width * height)_.
 //!
-//! For grid-independent automata, see [`life_like::Automaton`](super::life_like::Automaton).
+//! When the rule only looks at a limited neighborhood of each cell, change
+//! tracking can be enabled with [`InitBuilder::track_changes()`]. The automaton
+//! then only re-evaluates cells around the ones that changed in the previous
+//! generation, which brings the time complexity down to `O(k)`, where `k` is
+//! the **number of changed cells**.
+//!
+//! For grid-independent automata, see [`life_like::Automaton`].
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    iter, mem,
+    ops::Deref,
+};
 
 use crate::{
-    engine::{ExecutionState, Pos},
+    engine::{
+        self, convert,
+        cycle::CycleDetector,
+        history::{History, SavedHistory},
+        life_like,
+        observer::{Observer, Observers},
+        ExecutionState, Pos, Rect, Stats,
+    },
     grid_map,
 };
 
 // TODO:
 // - Allow multiple `DataFn`s in `Automaton`
-// - Create a trait to unify automaton/builder methods
+// - Create a trait to unify builder methods
 
 pub type Grid<T> = Vec<Vec<T>>;
 pub type StepFn<S, D> = fn(Pos, S, D) -> S;
 pub type DataFn<S, D> = fn(Pos, &S, &Grid<S>) -> D;
+pub type TimedDataFn<S, D> = fn(Pos, &S, &Grid<S>, u64) -> D;
+pub type GridHistory<S> = History<Grid<S>, Vec<(Pos, S)>>;
+pub type SavedGridHistory<S> = SavedHistory<Grid<S>, Vec<(Pos, S)>>;
 
 /// The main struct that contains the state of an automaton.
 ///
@@ -26,8 +52,353 @@ pub type DataFn<S, D> = fn(Pos, &S, &Grid<S>) -> D;
 pub struct Automaton<S, D = ()> {
     cells: Grid<S>,
     generations_left: Option<u32>,
+    /// Number of generations computed so far.
+    generation: u64,
+    /// Generation at which the generation limit was reached, so that going
+    /// back in history doesn't extend the limit.
+    limit_reached_at: Option<u64>,
     step_fn: StepFn<S, D>,
-    data_fn: DataFn<S, D>,
+    data_fn: Collector<S, D>,
+    tracker: Option<ChangeTracker<S>>,
+    auto_grow: Option<AutoGrow<S>>,
+    history: Option<GridHistory<S>>,
+    cycle_check: Option<CycleCheck<S>>,
+    stats: Option<StatsTracker<S>>,
+    observers: Option<Observers<Grid<S>, S>>,
+}
+
+/// Percentage of active cells above which a tracked automaton falls back to
+/// sweeping the whole grid.
+pub const DEFAULT_SWEEP_THRESHOLD: u8 = 25;
+
+/// Change tracking settings, see [`InitBuilder::track_changes()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrackingConfig {
+    /// Radius of the neighborhood the rule depends on.
+    pub radius: u8,
+    /// Percentage of active cells above which the whole grid is evaluated.
+    pub sweep_threshold: u8,
+}
+
+/// Automatic growth settings, see [`InitBuilder::grow_on_edge()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AutoGrowConfig<S> {
+    /// Number of cells to add to each side.
+    pub amount: usize,
+    /// State of newly added cells.
+    pub fill: S,
+}
+
+/// Cycle detection settings, see [`InitBuilder::detect_cycles()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CycleConfig<S> {
+    /// Longest period that can be detected.
+    pub max_period: u32,
+    /// State of dead cells.
+    pub dead: S,
+}
+
+/// The state of an [`Automaton`] without its functions.
+///
+/// Returned by [`Automaton::snapshot()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint<S> {
+    pub cells: Grid<S>,
+    /// See [`Automaton::generation()`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub generation: u64,
+    pub generations_left: Option<u32>,
+    pub tracking: Option<TrackingConfig>,
+    pub auto_grow: Option<AutoGrowConfig<S>>,
+    /// Recorded history, see [`Automaton::history()`].
+    pub history: Option<SavedGridHistory<S>>,
+    /// Cycle detection settings, see [`Automaton::max_period()`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cycles: Option<CycleConfig<S>>,
+    /// State of dead cells if statistics are enabled, see
+    /// [`Automaton::stats()`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub track_stats: Option<S>,
+}
+
+/// A data collection function, see [`InitBuilder::map()`] and
+/// [`InitBuilder::map_timed()`].
+enum Collector<S, D> {
+    Plain(DataFn<S, D>),
+    Timed(TimedDataFn<S, D>),
+}
+
+impl<S, D> Clone for Collector<S, D> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S, D> Copy for Collector<S, D> {}
+
+impl<S, D> Collector<S, D> {
+    fn collect(&self, pos: Pos, state: &S, grid: &Grid<S>, generation: u64) -> D {
+        match self {
+            Self::Plain(data_fn) => data_fn(pos, state, grid),
+            Self::Timed(data_fn) => data_fn(pos, state, grid, generation),
+        }
+    }
+}
+
+/// Keeps track of the cells that changed in the last generation.
+///
+/// See [`InitBuilder::track_changes()`].
+struct ChangeTracker<S> {
+    /// Radius of the neighborhood the rule depends on.
+    radius: u8,
+    /// Percentage of active cells above which the whole grid is evaluated.
+    sweep_threshold: u8,
+    /// Cells changed in the last generation, `None` if unknown.
+    changed: Option<Vec<Pos>>,
+    /// Reusable buffer marking cells scheduled for evaluation.
+    mask: Vec<bool>,
+    clone_fn: fn(&S) -> S,
+    eq_fn: fn(&S, &S) -> bool,
+}
+
+impl<S: Clone + PartialEq> ChangeTracker<S> {
+    fn new(radius: u8) -> Self {
+        Self {
+            radius,
+            sweep_threshold: DEFAULT_SWEEP_THRESHOLD,
+            changed: None,
+            mask: Vec::new(),
+            clone_fn: S::clone,
+            eq_fn: S::eq,
+        }
+    }
+}
+
+/// Grows the grid whenever a cell on its edge differs from the fill state.
+///
+/// See [`InitBuilder::grow_on_edge()`].
+struct AutoGrow<S> {
+    /// Number of cells to add to each side.
+    amount: usize,
+    /// State of newly added cells.
+    fill: S,
+    clone_fn: fn(&S) -> S,
+    eq_fn: fn(&S, &S) -> bool,
+}
+
+impl<S: Clone + PartialEq> AutoGrow<S> {
+    fn new(amount: usize, fill: S) -> Option<Self> {
+        (amount > 0).then(|| Self {
+            amount,
+            fill,
+            clone_fn: S::clone,
+            eq_fn: S::eq,
+        })
+    }
+}
+
+impl<S> AutoGrow<S> {
+    /// Returns the number of cells to add to each side of the grid (see
+    /// [`pad_grid()`]), which is `amount` for the sides that have a cell
+    /// differing from the fill state and 0 for the rest.
+    fn padding(&self, grid: &Grid<S>) -> [usize; 4] {
+        let is_active = |state| !(self.eq_fn)(state, &self.fill);
+        let grow = |is_touched: bool| if is_touched { self.amount } else { 0 };
+
+        [
+            grow(grid.first().into_iter().flatten().any(is_active)),
+            grow(grid.iter().filter_map(|xs| xs.last()).any(is_active)),
+            grow(grid.last().into_iter().flatten().any(is_active)),
+            grow(grid.iter().filter_map(|xs| xs.first()).any(is_active)),
+        ]
+    }
+}
+
+/// Detects grids that died out or repeat a previous generation.
+///
+/// See [`InitBuilder::detect_cycles()`].
+struct CycleCheck<S> {
+    detector: CycleDetector<Grid<S>>,
+    /// State of dead cells.
+    dead: S,
+    eq_fn: fn(&S, &S) -> bool,
+}
+
+impl<S: Hash + PartialEq> CycleCheck<S> {
+    fn new(max_period: u32, dead: S) -> Self {
+        Self {
+            detector: CycleDetector::new(max_period, hash_grid),
+            dead,
+            eq_fn: S::eq,
+        }
+    }
+}
+
+impl<S> CycleCheck<S> {
+    /// Records a newly computed generation, returning
+    /// [`ExecutionState::Extinct`] or [`ExecutionState::Stabilized`] if it
+    /// applies.
+    fn check(&mut self, grid: &Grid<S>) -> Option<ExecutionState> {
+        let period = self.detector.record(grid);
+
+        if grid
+            .iter()
+            .flatten()
+            .all(|state| (self.eq_fn)(state, &self.dead))
+        {
+            Some(ExecutionState::Extinct)
+        } else {
+            period.map(|period| ExecutionState::Stabilized { period })
+        }
+    }
+}
+
+/// Keeps [`Stats`] up to date.
+///
+/// See [`InitBuilder::track_stats()`].
+struct StatsTracker<S> {
+    stats: Stats<S>,
+    /// State of dead cells.
+    dead: S,
+    /// `stats` no longer match the grid (e.g. after an edit) and have to be
+    /// recounted.
+    is_stale: bool,
+    eq_fn: fn(&S, &S) -> bool,
+    recount_fn: fn(&mut Self, &Grid<S>),
+    change_fn: fn(&mut Self, Pos, &S, &S),
+}
+
+impl<S: Clone + Hash + Eq> StatsTracker<S> {
+    fn new(dead: S, grid: &Grid<S>) -> Self {
+        let mut tracker = Self {
+            stats: Stats::default(),
+            dead,
+            is_stale: true,
+            eq_fn: S::eq,
+            recount_fn: Self::recount_cells,
+            change_fn: Self::record_state_change,
+        };
+        tracker.recount(grid);
+
+        tracker
+    }
+
+    fn recount_cells(&mut self, grid: &Grid<S>) {
+        let dead = &self.dead;
+
+        self.stats.recount(
+            iter_grid(grid)
+                .filter(|(_, _, state)| *state != dead)
+                .map(|(x, y, state)| ((x, y), state)),
+        );
+    }
+
+    fn record_state_change(&mut self, pos: Pos, old: &S, new: &S) {
+        let (was_alive, is_alive) = (old != &self.dead, new != &self.dead);
+
+        if was_alive {
+            self.stats.remove_state(old);
+        }
+
+        if is_alive {
+            self.stats.add_state(new);
+        }
+
+        self.record_step(was_alive, is_alive);
+
+        match (was_alive, is_alive) {
+            (false, true) => self.stats.include(pos),
+            // The bounding box might shrink
+            (true, false) => {
+                self.is_stale |= self
+                    .stats
+                    .bounding_box
+                    .is_none_or(|rect| rect.is_on_edge(pos));
+            }
+            _ => (),
+        }
+    }
+}
+
+impl<S> StatsTracker<S> {
+    fn is_alive(&self, state: &S) -> bool {
+        !(self.eq_fn)(state, &self.dead)
+    }
+
+    /// Recounts the stats from the whole grid, keeping births and deaths.
+    fn recount(&mut self, grid: &Grid<S>) {
+        (self.recount_fn)(self, grid);
+        self.is_stale = false;
+    }
+
+    /// Records a cell that changed its state during a step, keeping all
+    /// stats up to date.
+    fn record_change(&mut self, pos: Pos, old: &S, new: &S) {
+        (self.change_fn)(self, pos, old, new);
+    }
+
+    /// Records a cell evaluated during a step, only counting births and
+    /// deaths.
+    const fn record_step(&mut self, was_alive: bool, is_alive: bool) {
+        match (was_alive, is_alive) {
+            (false, true) => {
+                self.stats.births = self.stats.births.saturating_add(1);
+                self.stats.population = self.stats.population.saturating_add(1);
+            }
+            (true, false) => {
+                self.stats.deaths = self.stats.deaths.saturating_add(1);
+                self.stats.population = self.stats.population.saturating_sub(1);
+            }
+            _ => (),
+        }
+    }
+}
+
+impl<S> ChangeTracker<S> {
+    /// Returns `true` if `n` cells out of `total` is too many to track.
+    fn is_over_threshold(&self, n: usize, total: usize) -> bool {
+        n.saturating_mul(100) > total.saturating_mul(self.sweep_threshold.into())
+    }
+
+    /// Returns the cells that depend on the ones changed in the last
+    /// generation, or `None` if the whole grid has to be evaluated.
+    #[allow(
+        clippy::arithmetic_side_effects,
+        reason = "All indices are bounded by the grid size"
+    )]
+    fn active_cells(&mut self, (width, height): (usize, usize)) -> Option<Vec<Pos>> {
+        let changed = self.changed.as_ref()?;
+        let total = width.saturating_mul(height);
+
+        if self.is_over_threshold(changed.len(), total) {
+            return None;
+        }
+
+        let radius = usize::from(self.radius);
+        let mut active = Vec::new();
+
+        self.mask.clear();
+        self.mask.resize(total, false);
+
+        for &(x, y) in changed {
+            for ny in y.saturating_sub(radius)..(y + radius + 1).min(height) {
+                for nx in x.saturating_sub(radius)..(x + radius + 1).min(width) {
+                    let scheduled = &mut self.mask[ny * width + nx];
+
+                    if !*scheduled {
+                        *scheduled = true;
+                        active.push((nx, ny));
+                    }
+                }
+            }
+        }
+
+        (!self.is_over_threshold(active.len(), total)).then_some(active)
+    }
 }
 
 #[macro_export]
@@ -37,97 +408,1047 @@ macro_rules! impl_builder_misc {
         pub const fn generations(mut self, generations_limit: u32) -> Self {
             self.generations_limit = Some(generations_limit);
 
-            self
+            self
+        }
+    };
+}
+
+impl Automaton<(), ()> {
+    /// Returns a builder to help construct an automaton.
+    pub const fn build(grid_size: (usize, usize)) -> AutomatonBuilder {
+        AutomatonBuilder { grid_size }
+    }
+}
+
+impl<S, D> Automaton<S, D> {
+    /// Computes the next generation.
+    ///
+    /// If cycle detection is enabled (see [`InitBuilder::detect_cycles()`]),
+    /// [`ExecutionState::Extinct`] and [`ExecutionState::Stabilized`] take
+    /// precedence over [`ExecutionState::Remaining`] and
+    /// [`ExecutionState::Infinite`].
+    pub fn step(&mut self) -> ExecutionState {
+        if self.is_finished() {
+            self.limit_reached_at.get_or_insert(self.generation);
+        }
+
+        let mut observers = self.observers.take();
+
+        if let Some(observers) = &mut observers {
+            observers.before_step(&self.cells);
+        }
+
+        if let Some(history) = &mut self.history {
+            history.sync(&self.cells);
+        }
+
+        if let Some(cycle_check) = &mut self.cycle_check {
+            cycle_check.detector.seed(&self.cells);
+        }
+
+        let mut stats_tracker = self.stats.take();
+
+        if let Some(stats_tracker) = &mut stats_tracker {
+            stats_tracker.stats.births = 0;
+            stats_tracker.stats.deaths = 0;
+        }
+
+        match self.tracker.take() {
+            Some(mut tracker) => {
+                self.step_tracked(&mut tracker, stats_tracker.as_mut(), observers.as_mut());
+                self.tracker = Some(tracker);
+            }
+            None => self.step_full(stats_tracker.as_mut(), observers.as_mut()),
+        }
+
+        self.generation = self.generation.saturating_add(1);
+
+        self.stats = stats_tracker;
+
+        if let Some(auto_grow) = self.auto_grow.take() {
+            let padding = auto_grow.padding(&self.cells);
+
+            if padding != [0; 4] {
+                let fill = || (auto_grow.clone_fn)(&auto_grow.fill);
+
+                self.cells = pad_grid(mem::take(&mut self.cells), padding, fill);
+                self.mark_all_changed();
+            }
+
+            self.auto_grow = Some(auto_grow);
+        }
+
+        if let Some(stats) = self.stats.as_mut().filter(|stats| stats.is_stale) {
+            stats.recount(&self.cells);
+        }
+
+        if let Some(history) = &mut self.history {
+            history.record(&self.cells);
+        }
+
+        let cycle_state = self
+            .cycle_check
+            .as_mut()
+            .and_then(|cycle_check| cycle_check.check(&self.cells));
+
+        // Return proper execution state signal
+        let state = self.generations_left.map_or(ExecutionState::Infinite, |x| {
+            x.checked_sub(1).map_or(ExecutionState::Finished, |y| {
+                self.generations_left = Some(y);
+                ExecutionState::Remaining(y)
+            })
+        });
+
+        let state = match (state, cycle_state) {
+            (ExecutionState::Finished, _) => ExecutionState::Finished,
+            (_, Some(cycle_state)) => cycle_state,
+            (state, None) => state,
+        };
+
+        if let Some(observers) = &mut observers {
+            observers.after_step(&self.cells, state);
+        }
+
+        self.observers = observers;
+
+        state
+    }
+
+    /// Evaluates every cell in the grid.
+    fn step_full(
+        &mut self,
+        mut stats: Option<&mut StatsTracker<S>>,
+        mut observers: Option<&mut Observers<Grid<S>, S>>,
+    ) {
+        // Get cells data
+        // OPTIM: Prealloc `cells_data` in `Self::new()` and reuse it
+        let mut cells_data: Vec<Vec<D>> = grid_map!(self.cells.iter(), |pos, state| {
+            self.data_fn
+                .collect(pos, state, &self.cells, self.generation)
+        })
+        .collect();
+
+        // Run step function
+        self.cells = mem::take(&mut self.cells)
+            .into_iter()
+            .enumerate()
+            .map(|(y, xs)| {
+                xs.into_iter()
+                    .enumerate()
+                    .map(|(x, state)| {
+                        let was_alive = stats.as_ref().map(|stats| stats.is_alive(&state));
+                        let old_state = observers
+                            .as_ref()
+                            .and_then(|observers| observers.save_state(&state));
+
+                        // NOTE: `cells_data` is never read from, so we can zero
+                        // out its elements
+                        #[allow(
+                            unsafe_code,
+                            clippy::mem_replace_with_uninit,
+                            reason = "The value is never read again"
+                        )]
+                        let new_state = unsafe {
+                            (self.step_fn)(
+                                (x, y),
+                                state,
+                                mem::replace(&mut cells_data[y][x], mem::zeroed()),
+                            )
+                        };
+
+                        if let (Some(stats), Some(was_alive)) = (stats.as_deref_mut(), was_alive) {
+                            stats.record_step(was_alive, stats.is_alive(&new_state));
+                        }
+
+                        if let (Some(observers), Some(old_state)) =
+                            (observers.as_deref_mut(), &old_state)
+                        {
+                            observers.cell_evaluated((x, y), Some(old_state), Some(&new_state));
+                        }
+
+                        new_state
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // States are consumed by the step function, so they have to be
+        // recounted
+        if let Some(stats) = stats {
+            stats.is_stale = true;
+        }
+    }
+
+    /// Evaluates only the cells that could have changed, recording the ones
+    /// that did.
+    fn step_tracked(
+        &mut self,
+        tracker: &mut ChangeTracker<S>,
+        mut stats_tracker: Option<&mut StatsTracker<S>>,
+        mut observers: Option<&mut Observers<Grid<S>, S>>,
+    ) {
+        let active = tracker
+            .active_cells(self.grid_size())
+            .unwrap_or_else(|| iter_grid(&self.cells).map(|(x, y, _)| (x, y)).collect());
+
+        // NOTE: All data has to be collected before any cell is updated
+        let cells_data: Vec<D> = active
+            .iter()
+            .map(|&(x, y)| {
+                self.data_fn
+                    .collect((x, y), &self.cells[y][x], &self.cells, self.generation)
+            })
+            .collect();
+
+        let mut changed = Vec::new();
+
+        for ((x, y), data) in active.into_iter().zip(cells_data) {
+            let state = &mut self.cells[y][x];
+            let new_state = (self.step_fn)((x, y), (tracker.clone_fn)(state), data);
+
+            if !(tracker.eq_fn)(state, &new_state) {
+                if let Some(stats_tracker) = stats_tracker.as_deref_mut() {
+                    stats_tracker.record_change((x, y), state, &new_state);
+                }
+
+                if let Some(observers) = observers.as_deref_mut() {
+                    observers.cell_evaluated((x, y), Some(state), Some(&new_state));
+                }
+
+                *state = new_state;
+                changed.push((x, y));
+            }
+        }
+
+        tracker.changed = Some(changed);
+    }
+
+    /// Returns an immutable reference to the cell grid.
+    pub const fn cells(&self) -> &Grid<S> {
+        &self.cells
+    }
+
+    /// Returns a mutable view of the cell grid.
+    ///
+    /// **Note:** Since the view doesn't know which cells were modified, the
+    /// next generation is computed for the whole grid.
+    pub const fn cells_mut(&mut self) -> CellsMut<'_, S, D> {
+        CellsMut { automaton: self }
+    }
+
+    /// Returns the state of the cell at `pos`, or `None` if it's out of
+    /// bounds.
+    pub fn get(&self, (x, y): Pos) -> Option<&S> {
+        self.cells.get(y)?.get(x)
+    }
+
+    /// Sets the state of the cell at `pos`, returning the previous one.
+    ///
+    /// Returns `None` (and drops `state`) if `pos` is out of bounds.
+    pub fn set(&mut self, (x, y): Pos, state: S) -> Option<S> {
+        let old_state = mem::replace(self.cells.get_mut(y)?.get_mut(x)?, state);
+        self.mark_changed((x, y));
+
+        Some(old_state)
+    }
+
+    /// Shrinks the grid to the cells inside of `rect`. Parts of `rect` that
+    /// lie outside of the grid are ignored.
+    ///
+    /// **Note:** Cells' positions are shifted by `(-rect.x, -rect.y)`.
+    pub fn crop(&mut self, rect: Rect) {
+        self.cells = mem::take(&mut self.cells)
+            .into_iter()
+            .skip(rect.y)
+            .take(rect.height)
+            .map(|xs| xs.into_iter().skip(rect.x).take(rect.width).collect())
+            .collect();
+        self.mark_all_changed();
+    }
+
+    /// Returns the cells grid dimensions.
+    pub fn grid_size(&self) -> (usize, usize) {
+        self.cells
+            .first()
+            .map(Vec::len)
+            .map_or((0, 0), |l| (l, self.cells.len()))
+    }
+
+    /// Like [`grid_size()`](Self::grid_size), but doesn't perform bounds checks.
+    ///
+    /// # Safety
+    /// This function will panic if the grid's width is 0.
+    pub unsafe fn grid_size_unchecked(&self) -> (usize, usize) {
+        (self.cells[0].len(), self.cells.len())
+    }
+
+    /// Returns the number of generations computed so far.
+    ///
+    /// Going back in history (see [`Automaton::step_back()`] and
+    /// [`Automaton::seek()`]) restores the number of the restored
+    /// generation.
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns `true` if the generation limit has been exceeded.
+    pub fn is_finished(&self) -> bool {
+        self.generations_left == Some(0)
+    }
+
+    /// Returns `true` if the generation limit is not set.
+    pub const fn is_infinite(&self) -> bool {
+        self.generations_left.is_none()
+    }
+
+    /// Returns a [`Generations`] view, which computes one generation at a
+    /// time until the generation limit is reached.
+    pub const fn generations(&mut self) -> Generations<'_, S, D> {
+        Generations { automaton: self }
+    }
+
+    /// Computes up to `generations` generations, stopping early if the
+    /// generation limit is reached.
+    ///
+    /// Returns the state returned by the last step.
+    pub fn run_for(&mut self, generations: u32) -> ExecutionState {
+        let mut state = ExecutionState::from_generations_left(self.generations_left);
+
+        for _ in 0..generations {
+            if self.is_finished() {
+                break;
+            }
+
+            state = self.step();
+        }
+
+        state
+    }
+
+    /// Computes generations until `predicate` returns `true` for the
+    /// automaton (checked after each step), or the generation limit is
+    /// reached.
+    ///
+    /// Returns the state returned by the last step.
+    pub fn run_until<F: FnMut(&Self) -> bool>(&mut self, mut predicate: F) -> ExecutionState {
+        let mut state = ExecutionState::from_generations_left(self.generations_left);
+
+        while !self.is_finished() {
+            state = self.step();
+
+            if predicate(self) {
+                break;
+            }
+        }
+
+        state
+    }
+
+    /// Returns `true` if change tracking is enabled.
+    ///
+    /// See [`InitBuilder::track_changes()`].
+    pub const fn is_tracking_changes(&self) -> bool {
+        self.tracker.is_some()
+    }
+
+    /// Returns the positions of cells that changed in the last generation.
+    ///
+    /// Returns `None` if change tracking is disabled or no generation has
+    /// been computed yet.
+    pub fn changed_cells(&self) -> Option<&[Pos]> {
+        self.tracker.as_ref()?.changed.as_deref()
+    }
+
+    /// Sets the percentage of active cells above which the whole grid is
+    /// evaluated instead of only the changed regions.
+    ///
+    /// Has no effect if change tracking is disabled.
+    pub const fn set_sweep_threshold(&mut self, percent: u8) {
+        if let Some(tracker) = &mut self.tracker {
+            tracker.sweep_threshold = percent;
+        }
+    }
+
+    /// Forgets the cells changed in the last generation, so that the next
+    /// generation is computed for the whole grid.
+    fn mark_all_changed(&mut self) {
+        if let Some(tracker) = &mut self.tracker {
+            tracker.changed = None;
+        }
+
+        self.mark_edited();
+    }
+
+    /// Schedules the cell's neighborhood for evaluation in the next
+    /// generation.
+    fn mark_changed(&mut self, pos: Pos) {
+        if let Some(changed) = self.tracker.as_mut().and_then(|t| t.changed.as_mut()) {
+            changed.push(pos);
+        }
+
+        self.mark_edited();
+    }
+
+    /// Notifies the history, cycle detection and statistics that the grid
+    /// was modified outside of a step.
+    fn mark_edited(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.mark_dirty();
+        }
+
+        if let Some(cycle_check) = &mut self.cycle_check {
+            cycle_check.detector.reset();
+        }
+
+        if let Some(stats) = &mut self.stats {
+            stats.is_stale = true;
+        }
+    }
+
+    /// Returns the statistics of the last computed generation, or `None` if
+    /// they're disabled.
+    ///
+    /// **Note:** Edits made since the last step are only taken into account
+    /// after the next one.
+    ///
+    /// See [`InitBuilder::track_stats()`].
+    pub fn stats(&self) -> Option<&Stats<S>> {
+        self.stats.as_ref().map(|stats| &stats.stats)
+    }
+
+    /// Returns the longest period that cycle detection looks for, or `None`
+    /// if it's disabled.
+    ///
+    /// See [`InitBuilder::detect_cycles()`].
+    pub fn max_period(&self) -> Option<u32> {
+        self.cycle_check
+            .as_ref()
+            .map(|cycle_check| cycle_check.detector.max_period())
+    }
+
+    /// Returns the recorded history, if enabled.
+    ///
+    /// See [`InitBuilder::history()`].
+    pub const fn history(&self) -> Option<&GridHistory<S>> {
+        self.history.as_ref()
+    }
+
+    /// Returns a mutable reference to the recorded history, if enabled.
+    pub const fn history_mut(&mut self) -> Option<&mut GridHistory<S>> {
+        self.history.as_mut()
+    }
+
+    /// Restores the previous recorded generation.
+    ///
+    /// Returns `false` if history is disabled or there is nothing to go back
+    /// to.
+    pub fn step_back(&mut self) -> bool {
+        self.history
+            .as_ref()
+            .and_then(History::previous_generation)
+            .is_some_and(|generation| self.rewind(generation))
+    }
+
+    /// Moves to the given generation (see [`generation()`](Self::generation)),
+    /// restoring it from history or computing it.
+    ///
+    /// Returns `false` if history is disabled or the generation was thinned
+    /// out.
+    pub fn seek(&mut self, generation: u64) -> bool {
+        let Some(latest) = self.history.as_ref().map(History::latest_generation) else {
+            return false;
+        };
+
+        if generation <= latest {
+            return self.rewind(generation);
+        }
+
+        for _ in latest..generation {
+            self.step();
+        }
+
+        true
+    }
+
+    /// Restores a recorded generation, discarding all newer ones.
+    fn rewind(&mut self, generation: u64) -> bool {
+        let Some(history) = &mut self.history else {
+            return false;
+        };
+        let Some(cells) = history.rewind(generation) else {
+            return false;
+        };
+        // The generation at which the limit is (or was) reached
+        let limit_end = self.limit_reached_at.or_else(|| {
+            self.generations_left
+                .map(|x| self.generation.saturating_add(x.into()))
+        });
+
+        self.cells = cells;
+        self.generation = generation;
+        self.generations_left =
+            limit_end.map(|end| u32::try_from(end.saturating_sub(generation)).unwrap_or(u32::MAX));
+        self.limit_reached_at = self.limit_reached_at.filter(|end| *end <= generation);
+
+        if let Some(tracker) = &mut self.tracker {
+            tracker.changed = None;
+        }
+
+        if let Some(cycle_check) = &mut self.cycle_check {
+            cycle_check.detector.reset();
+        }
+
+        if let Some(stats) = &mut self.stats {
+            stats.recount(&self.cells);
+        }
+
+        true
+    }
+}
+
+impl<S: Clone, D> Automaton<S, D> {
+    /// Resizes the grid to `(width, height)`, adding or removing cells at the
+    /// right and bottom edges. New cells are set to `fill`.
+    pub fn resize(&mut self, (width, height): (usize, usize), fill: S) {
+        for xs in &mut self.cells {
+            xs.resize(width, fill.clone());
+        }
+
+        self.cells.resize_with(height, || vec![fill.clone(); width]);
+        self.mark_all_changed();
+    }
+
+    /// Returns the state of the automaton, which can be used to
+    /// [`restore()`](Self::restore) it later.
+    ///
+    /// The recorded history and the cycle detection and statistics settings
+    /// are saved as well. Observers aren't saved.
+    pub fn snapshot(&self) -> Checkpoint<S> {
+        Checkpoint {
+            cells: self.cells.clone(),
+            generation: self.generation,
+            generations_left: self.generations_left,
+            tracking: self.tracker.as_ref().map(|t| TrackingConfig {
+                radius: t.radius,
+                sweep_threshold: t.sweep_threshold,
+            }),
+            auto_grow: self.auto_grow.as_ref().map(|a| AutoGrowConfig {
+                amount: a.amount,
+                fill: a.fill.clone(),
+            }),
+            history: self.history.as_ref().map(History::save),
+            cycles: self.cycle_check.as_ref().map(|c| CycleConfig {
+                max_period: c.detector.max_period(),
+                dead: c.dead.clone(),
+            }),
+            track_stats: self.stats.as_ref().map(|s| s.dead.clone()),
+        }
+    }
+
+    /// Sets all cells to `fill`.
+    pub fn clear(&mut self, fill: S) {
+        for state in self.cells.iter_mut().flatten() {
+            state.clone_from(&fill);
+        }
+
+        self.mark_all_changed();
+    }
+
+    /// Sets all cells inside of `rect` to `state`. Parts of `rect` that lie
+    /// outside of the grid are ignored.
+    pub fn fill_rect(&mut self, rect: Rect, state: S) {
+        let (width, height) = self.grid_size();
+        let rect = Rect::new(
+            (rect.x, rect.y),
+            (
+                rect.width.min(width.saturating_sub(rect.x)),
+                rect.height.min(height.saturating_sub(rect.y)),
+            ),
+        );
+
+        for pos in rect.positions() {
+            self.set(pos, state.clone());
+        }
+    }
+
+    /// Adds the given number of cells to each side of the grid. New cells
+    /// are set to `fill`.
+    ///
+    /// **Note:** Cells' positions are shifted by `(left, top)`.
+    pub fn pad(&mut self, top: usize, right: usize, bottom: usize, left: usize, fill: S) {
+        self.cells = pad_grid(
+            mem::take(&mut self.cells),
+            [top, right, bottom, left],
+            || fill.clone(),
+        );
+        self.mark_all_changed();
+    }
+}
+
+impl<S: Clone + PartialEq, D> Automaton<S, D> {
+    /// Enables change tracking, see [`InitBuilder::track_changes()`].
+    ///
+    /// The next generation is always computed for the whole grid.
+    pub fn track_changes(&mut self, radius: u8) {
+        self.tracker = Some(ChangeTracker::new(radius));
+    }
+
+    /// Enables automatic growth, see [`InitBuilder::grow_on_edge()`].
+    ///
+    /// An `amount` of 0 disables it.
+    pub fn grow_on_edge(&mut self, amount: usize, fill: S) {
+        self.auto_grow = AutoGrow::new(amount, fill);
+    }
+
+    /// Computes generations until the grid stops changing, or the
+    /// generation limit is reached.
+    ///
+    /// Returns [`ExecutionState::Stabilized`] with a period of 1 once a
+    /// generation is the same as the previous one. If cycle detection is
+    /// enabled, also stops once a step returns [`ExecutionState::Extinct`]
+    /// or [`ExecutionState::Stabilized`]. Otherwise, returns the state
+    /// returned by the last step.
+    ///
+    /// **Note:** Without a generation limit or cycle detection, this never
+    /// returns for patterns that keep changing (e.g. oscillators).
+    pub fn run_while_changing(&mut self) -> ExecutionState {
+        let mut state = ExecutionState::from_generations_left(self.generations_left);
+
+        while !self.is_finished() {
+            // Change tracking already knows whether anything changed
+            let previous = (!self.is_tracking_changes()).then(|| self.cells.clone());
+
+            state = self.step();
+
+            let is_static = previous.map_or_else(
+                || self.changed_cells().is_some_and(<[Pos]>::is_empty),
+                |previous| previous == self.cells,
+            );
+
+            if is_static {
+                return ExecutionState::Stabilized { period: 1 };
+            }
+
+            if matches!(
+                state,
+                ExecutionState::Extinct | ExecutionState::Stabilized { .. }
+            ) {
+                break;
+            }
+        }
+
+        state
+    }
+
+    /// Starts recording history, see [`InitBuilder::history()`].
+    ///
+    /// The current grid is recorded as the current
+    /// [`generation()`](Self::generation).
+    pub fn record_history(&mut self, budget: usize) {
+        self.history = Some(History::new(&self.cells, self.generation, budget));
+    }
+
+    /// Subscribes an observer, see [`InitBuilder::observe()`].
+    pub fn observe<O: Observer<Grid<S>, S> + 'static>(&mut self, observer: O) {
+        self.observers
+            .get_or_insert_with(Observers::new)
+            .push(Box::new(observer));
+    }
+
+    /// Unsubscribes all observers.
+    pub fn clear_observers(&mut self) {
+        self.observers = None;
+    }
+}
+
+impl<S: Hash + PartialEq, D> Automaton<S, D> {
+    /// Enables cycle detection, see [`InitBuilder::detect_cycles()`].
+    pub fn detect_cycles(&mut self, max_period: u32, dead: S) {
+        self.cycle_check = Some(CycleCheck::new(max_period, dead));
+    }
+}
+
+impl<S: Clone + Hash + Eq, D> Automaton<S, D> {
+    /// Enables statistics, see [`InitBuilder::track_stats()`].
+    ///
+    /// They're counted from the current grid right away.
+    pub fn track_stats(&mut self, dead: S) {
+        self.stats = Some(StatsTracker::new(dead, &self.cells));
+    }
+
+    /// Restores an automaton saved with [`snapshot()`](Self::snapshot),
+    /// using the given functions.
+    ///
+    /// The recorded history is restored as well, unless it's invalid (see
+    /// [`History::restore()`]), in which case it starts anew with the
+    /// restored grid. Cycle detection starts anew with the saved settings,
+    /// and statistics are recounted from the restored grid.
+    pub fn restore(
+        checkpoint: Checkpoint<S>,
+        step_fn: StepFn<S, D>,
+        data_fn: DataFn<S, D>,
+    ) -> Self {
+        Self::restore_with(checkpoint, step_fn, Collector::Plain(data_fn))
+    }
+
+    /// Like [`restore()`](Self::restore), but with a data function that
+    /// takes the current generation (see [`InitBuilder::map_timed()`]).
+    pub fn restore_timed(
+        checkpoint: Checkpoint<S>,
+        step_fn: StepFn<S, D>,
+        data_fn: TimedDataFn<S, D>,
+    ) -> Self {
+        Self::restore_with(checkpoint, step_fn, Collector::Timed(data_fn))
+    }
+
+    fn restore_with(
+        checkpoint: Checkpoint<S>,
+        step_fn: StepFn<S, D>,
+        data_fn: Collector<S, D>,
+    ) -> Self {
+        let Checkpoint {
+            cells,
+            generation,
+            generations_left,
+            tracking,
+            auto_grow,
+            history,
+            cycles,
+            track_stats,
+        } = checkpoint;
+
+        Self {
+            history: history.map(|saved| {
+                let config = saved.config;
+
+                History::restore(saved)
+                    .unwrap_or_else(|| History::with_config(&cells, generation, config))
+            }),
+            stats: track_stats.map(|dead| StatsTracker::new(dead, &cells)),
+            cells,
+            generation,
+            limit_reached_at: None,
+            generations_left,
+            step_fn,
+            data_fn,
+            tracker: tracking.map(|config| {
+                let mut tracker = ChangeTracker::new(config.radius);
+                tracker.sweep_threshold = config.sweep_threshold;
+                tracker
+            }),
+            auto_grow: auto_grow.and_then(|config| AutoGrow::new(config.amount, config.fill)),
+            cycle_check: cycles.map(|config| CycleCheck::new(config.max_period, config.dead)),
+            observers: None,
+        }
+    }
+}
+
+/// A mutable view of an automaton's cell grid.
+///
+/// Returned by [`Automaton::cells_mut()`].
+pub struct CellsMut<'a, S, D> {
+    automaton: &'a mut Automaton<S, D>,
+}
+
+impl<S, D> CellsMut<'_, S, D> {
+    /// Returns a mutable reference to the cell at `pos`, or `None` if it's
+    /// out of bounds.
+    pub fn get_mut(&mut self, (x, y): Pos) -> Option<&mut S> {
+        self.automaton.cells.get_mut(y)?.get_mut(x)
+    }
+
+    /// Returns an iterator over all cells along with their positions.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Pos, &mut S)> {
+        self.automaton
+            .cells
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(y, xs)| xs.iter_mut().enumerate().map(move |(x, s)| ((x, y), s)))
+    }
+}
+
+impl<S, D> Deref for CellsMut<'_, S, D> {
+    type Target = Grid<S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.automaton.cells
+    }
+}
+
+impl<S, D> Drop for CellsMut<'_, S, D> {
+    fn drop(&mut self) {
+        self.automaton.mark_all_changed();
+    }
+}
+
+/// Computes generations of an automaton one by one.
+///
+/// Returned by [`Automaton::generations()`].
+///
+/// # Examples
+/// ```rust
+/// # use lifers::engine::generic::AutomatonBuilder;
+/// # let mut game = AutomatonBuilder::new((4, 4))
+/// #     .init(|_| false)
+/// #     .generations(3)
+/// #     .run(|_, state, ()| state);
+/// let mut generations = game.generations();
+///
+/// while let Some((state, cells)) = generations.next() {
+///     println!("{state:?}: {} cells", cells.len());
+/// }
+///
+/// assert!(game.is_finished());
+/// ```
+pub struct Generations<'a, S, D> {
+    automaton: &'a mut Automaton<S, D>,
+}
+
+impl<S, D> Generations<'_, S, D> {
+    /// Computes the next generation, returning the state returned by
+    /// [`Automaton::step()`] along with the new grid.
+    ///
+    /// Returns `None` once the generation limit is reached.
+    #[allow(
+        clippy::should_implement_trait,
+        reason = "Grids borrow from `self`, which `Iterator` doesn't allow"
+    )]
+    pub fn next(&mut self) -> Option<(ExecutionState, &Grid<S>)> {
+        if self.automaton.is_finished() {
+            return None;
         }
-    };
-}
 
-impl Automaton<(), ()> {
-    /// Returns a builder to help construct an automaton.
-    pub const fn build(grid_size: (usize, usize)) -> AutomatonBuilder {
-        AutomatonBuilder { grid_size }
+        let state = self.automaton.step();
+
+        Some((state, &self.automaton.cells))
     }
 }
 
 impl<S, D> Automaton<S, D> {
-    /// Computes the next generation.
-    pub fn step(&mut self) -> ExecutionState {
-        // Get cells data
-        // OPTIM: Prealloc `cells_data` in `Self::new()` and reuse it
-        let mut cells_data: Vec<Vec<D>> =
-            grid_map!(self.cells.iter(), self.data_fn, &self.cells).collect();
+    /// Converts the automaton into a [`life_like::Automaton`] with the given
+    /// rule, keeping the generation counter and limit.
+    ///
+    /// See [`convert::to_sparse()`] for `offset` and `convert`.
+    pub fn to_life_like<T, E, F>(
+        &self,
+        offset: Pos<i64>,
+        convert: F,
+        radius: u8,
+        step_fn: life_like::StepFn<T, E>,
+        data_fn: life_like::DataFn<T, E>,
+    ) -> life_like::Automaton<T, E>
+    where
+        T: Clone + Hash + Eq,
+        F: Fn(&S) -> Option<T>,
+    {
+        let checkpoint = life_like::Checkpoint {
+            cells: convert::to_sparse(&self.cells, offset, convert),
+            radius,
+            generation: self.generation,
+            generations_left: self.generations_left,
+            history: None,
+            max_period: None,
+            track_stats: false,
+        };
 
-        // Run step function
-        self.cells = std::mem::take(&mut self.cells)
-            .into_iter()
-            .enumerate()
-            .map(|(y, xs)| {
-                xs.into_iter()
-                    .enumerate()
-                    .map(|(x, state)| {
-                        // NOTE: `cells_data` is never read from, so we can zero
-                        // out its elements
-                        #[allow(unsafe_code, clippy::mem_replace_with_uninit)]
-                        unsafe {
-                            (self.step_fn)(
-                                (x, y),
-                                state,
-                                std::mem::replace(&mut cells_data[y][x], std::mem::zeroed()),
-                            )
-                        }
-                    })
-                    .collect()
-            })
-            .collect();
+        life_like::Automaton::restore(checkpoint, step_fn, data_fn)
+    }
+}
 
-        // Return proper execution state signal
-        self.generations_left.map_or(ExecutionState::Infinite, |x| {
-            x.checked_sub(1).map_or(ExecutionState::Finished, |y| {
-                self.generations_left = Some(y);
-                ExecutionState::Remaining(y)
-            })
-        })
+impl<S, D> engine::Automaton for Automaton<S, D> {
+    type State = S;
+    type Coord = usize;
+
+    fn step(&mut self) -> ExecutionState {
+        self.step()
     }
 
-    /// Returns an immutable reference to the cell grid.
-    pub const fn cells(&self) -> &Grid<S> {
-        &self.cells
+    fn is_finished(&self) -> bool {
+        self.is_finished()
     }
 
-    /// Returns the cells grid dimensions.
-    pub fn grid_size(&self) -> (usize, usize) {
-        self.cells
-            .first()
-            .map(Vec::len)
-            .map_or((0, 0), |l| (l, self.cells.len()))
+    fn generation(&self) -> u64 {
+        self.generation
     }
 
-    /// Like [`grid_size()`](Self::grid_size), but doesn't perform bounds checks.
-    ///
-    /// # Safety
-    /// This function will panic if the grid's width is 0.
-    pub unsafe fn grid_size_unchecked(&self) -> (usize, usize) {
-        (self.cells[0].len(), self.cells.len())
+    fn generations_left(&self) -> Option<u32> {
+        self.generations_left
     }
 
-    /// Returns `true` if the generation limit has been exceeded.
-    pub fn is_finished(&self) -> bool {
-        self.generations_left.map_or(false, |x| x == 0)
+    fn get(&self, pos: Pos) -> Option<&S> {
+        self.get(pos)
     }
 
-    /// Returns `true` if the generation limit is not set.
-    pub fn is_infinite(&self) -> bool {
-        self.generations_left.is_none()
+    fn iter_cells(&self) -> impl Iterator<Item = (Pos, &S)> {
+        iter_grid(&self.cells).map(|(x, y, state)| ((x, y), state))
+    }
+
+    /// Returns the whole grid, unless it's empty.
+    fn bounding_box(&self) -> Option<Rect> {
+        let (width, height) = self.grid_size();
+
+        (width > 0 && height > 0).then(|| Rect::new((0, 0), (width, height)))
+    }
+}
+
+/// Pads the grid with cells produced by `fill`. `sides` are in the order of
+/// top, right, bottom, left.
+fn pad_grid<S, F: FnMut() -> S>(grid: Grid<S>, sides: [usize; 4], mut fill: F) -> Grid<S> {
+    let [top, right, bottom, left] = sides;
+    let width = grid
+        .first()
+        .map_or(0, Vec::len)
+        .saturating_add(left)
+        .saturating_add(right);
+    let mut padded = Vec::with_capacity(grid.len().saturating_add(top).saturating_add(bottom));
+
+    padded
+        .extend(iter::repeat_with(|| iter::repeat_with(&mut fill).take(width).collect()).take(top));
+
+    for xs in grid {
+        let mut row = Vec::with_capacity(width);
+
+        row.extend(iter::repeat_with(&mut fill).take(left));
+        row.extend(xs);
+        row.extend(iter::repeat_with(&mut fill).take(right));
+        padded.push(row);
     }
+
+    padded.extend(
+        iter::repeat_with(|| iter::repeat_with(&mut fill).take(width).collect()).take(bottom),
+    );
+
+    padded
+}
+
+/// Implements optional engine features for builders.
+macro_rules! impl_builder_options {
+    () => {
+        /// Enables change tracking.
+        ///
+        /// `radius` is the radius of the neighborhood that the rule reads
+        /// (in both data and step functions). Only cells within this radius
+        /// of the ones that changed in the last generation are re-evaluated.
+        ///
+        /// **Note:** Rules that depend on cells outside of `radius`, or on
+        /// anything other than the grid and cell position, will be computed
+        /// incorrectly.
+        pub fn track_changes(mut self, radius: u8) -> Self
+        where
+            S: Clone + PartialEq,
+        {
+            self.tracker = Some(ChangeTracker::new(radius));
+
+            self
+        }
+
+        /// Sets the percentage of active cells above which the whole grid is
+        /// evaluated instead. Defaults to [`DEFAULT_SWEEP_THRESHOLD`].
+        ///
+        /// Has no effect unless [`track_changes()`](Self::track_changes) was
+        /// called before.
+        pub const fn sweep_threshold(mut self, percent: u8) -> Self {
+            if let Some(tracker) = &mut self.tracker {
+                tracker.sweep_threshold = percent;
+            }
+
+            self
+        }
+
+        /// Makes the grid grow by `amount` cells on a side whenever a cell on
+        /// that edge differs from `fill` after a step. New cells are set to
+        /// `fill`.
+        ///
+        /// This allows patterns to evolve without hitting the grid's border.
+        ///
+        /// **Note:** Cells' positions are shifted by `amount` whenever the
+        /// grid grows at the top or on the left.
+        pub fn grow_on_edge(mut self, amount: usize, fill: S) -> Self
+        where
+            S: Clone + PartialEq,
+        {
+            self.auto_grow = AutoGrow::new(amount, fill);
+
+            self
+        }
+
+        /// Enables history recording, which allows to go back to previous
+        /// generations with [`Automaton::step_back()`] and
+        /// [`Automaton::seek()`].
+        ///
+        /// Once the history takes up more than `budget` bytes, older
+        /// generations are thinned out (see [`History`]). The initial grid is
+        /// recorded as generation 0.
+        pub fn history(mut self, budget: usize) -> Self
+        where
+            S: Clone + PartialEq,
+        {
+            self.history = Some(History::new(&self.grid, 0, budget));
+
+            self
+        }
+
+        /// Enables cycle detection.
+        ///
+        /// After each step, the grid is compared (by its hash) to the last
+        /// `max_period` generations. [`Automaton::step()`] then returns
+        /// [`ExecutionState::Stabilized`] when the grid repeats one of them,
+        /// or [`ExecutionState::Extinct`] when all cells are in the `dead`
+        /// state.
+        ///
+        /// Edits made between steps restart the detection.
+        pub fn detect_cycles(mut self, max_period: u32, dead: S) -> Self
+        where
+            S: Hash + PartialEq,
+        {
+            self.cycle_check = Some(CycleCheck::new(max_period, dead));
+
+            self
+        }
+
+        /// Enables statistics (see [`Stats`]), available through
+        /// [`Automaton::stats()`].
+        ///
+        /// Cells in the `dead` state are not counted as live. Births and
+        /// deaths are counted while the step is computed; when change
+        /// tracking is enabled, all other stats are updated only for the
+        /// cells that changed.
+        pub fn track_stats(mut self, dead: S) -> Self
+        where
+            S: Clone + Hash + Eq,
+        {
+            self.stats = Some(StatsTracker::new(dead, &self.grid));
+
+            self
+        }
+
+        /// Subscribes an observer, which is then notified about every step
+        /// (see [`Observer`]).
+        ///
+        /// Observers are notified in the order they were added. To access an
+        /// observer afterwards, pass it wrapped in an `Rc<RefCell<_>>`.
+        pub fn observe<O: Observer<Grid<S>, S> + 'static>(mut self, observer: O) -> Self
+        where
+            S: Clone + PartialEq,
+        {
+            self.observers
+                .get_or_insert_with(Observers::new)
+                .push(Box::new(observer));
+
+            self
+        }
+    };
 }
 
 /// A helper struct for building an [`Automaton`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AutomatonBuilder {
     grid_size: Pos,
 }
 
 impl AutomatonBuilder {
-    /// Creates a builder with the given grid size.
+    /// Creates a builder with the given grid size, as `(width, height)`.
     pub const fn new(grid_size: Pos) -> Self {
         Self { grid_size }
     }
@@ -137,13 +1458,19 @@ impl AutomatonBuilder {
     ///
     /// Returns an [`InitBuilder<S>`].
     pub fn init<S, F: Fn(Pos) -> S>(self, f: F) -> InitBuilder<S> {
-        let grid: Vec<Vec<_>> = (0..self.grid_size.0)
-            .map(|y| (0..self.grid_size.1).map(|x| (f)((x, y))).collect())
+        let grid: Vec<Vec<_>> = (0..self.grid_size.1)
+            .map(|y| (0..self.grid_size.0).map(|x| (f)((x, y))).collect())
             .collect();
 
         InitBuilder {
             grid,
             generations_limit: None,
+            tracker: None,
+            auto_grow: None,
+            history: None,
+            cycle_check: None,
+            stats: None,
+            observers: None,
         }
     }
 }
@@ -152,6 +1479,12 @@ impl AutomatonBuilder {
 pub struct InitBuilder<S> {
     grid: Grid<S>,
     generations_limit: Option<u32>,
+    tracker: Option<ChangeTracker<S>>,
+    auto_grow: Option<AutoGrow<S>>,
+    history: Option<GridHistory<S>>,
+    cycle_check: Option<CycleCheck<S>>,
+    stats: Option<StatsTracker<S>>,
+    observers: Option<Observers<Grid<S>, S>>,
 }
 
 impl<S> InitBuilder<S> {
@@ -162,23 +1495,52 @@ impl<S> InitBuilder<S> {
     pub fn run(self, f: StepFn<S, ()>) -> Automaton<S, ()> {
         Automaton {
             cells: self.grid,
+            generation: 0,
+            limit_reached_at: None,
             generations_left: self.generations_limit,
-            data_fn: |_, _, _| (),
+            data_fn: Collector::Plain(|_, _, _| ()),
             step_fn: f,
+            tracker: self.tracker,
+            auto_grow: self.auto_grow,
+            history: self.history,
+            cycle_check: self.cycle_check,
+            stats: self.stats,
+            observers: self.observers,
         }
     }
 
     /// Returns a [`MappedBuilder<S, D>`] with a given data collection function
     /// returning a value of type `D`.
     pub fn map<D>(self, f: DataFn<S, D>) -> MappedBuilder<S, D> {
+        self.map_with(Collector::Plain(f))
+    }
+
+    /// Like [`map()`](Self::map), but the data collection function also
+    /// takes the number of the generation the data is collected from (see
+    /// [`Automaton::generation()`]).
+    ///
+    /// The generation can be passed on to the step function as a part of
+    /// the data, which allows for time-dependent rules.
+    pub fn map_timed<D>(self, f: TimedDataFn<S, D>) -> MappedBuilder<S, D> {
+        self.map_with(Collector::Timed(f))
+    }
+
+    fn map_with<D>(self, data_fn: Collector<S, D>) -> MappedBuilder<S, D> {
         MappedBuilder {
             grid: self.grid,
-            data_fn: f,
-            generations_limit: None,
+            data_fn,
+            generations_limit: self.generations_limit,
+            tracker: self.tracker,
+            auto_grow: self.auto_grow,
+            history: self.history,
+            cycle_check: self.cycle_check,
+            stats: self.stats,
+            observers: self.observers,
         }
     }
 
     impl_builder_misc! { Self }
+    impl_builder_options! {}
 }
 
 /// A builder with a grid and a data collection function attached.
@@ -189,9 +1551,21 @@ pub struct MappedBuilder<S, D> {
     /// The cell grid.
     grid: Grid<S>,
     /// Data collection function.
-    data_fn: DataFn<S, D>,
+    data_fn: Collector<S, D>,
     /// Number of generations that the automaton will be limited to.
     generations_limit: Option<u32>,
+    /// Change tracking state, if enabled.
+    tracker: Option<ChangeTracker<S>>,
+    /// Automatic growth settings, if enabled.
+    auto_grow: Option<AutoGrow<S>>,
+    /// Recorded history, if enabled.
+    history: Option<GridHistory<S>>,
+    /// Cycle detection state, if enabled.
+    cycle_check: Option<CycleCheck<S>>,
+    /// Statistics, if enabled.
+    stats: Option<StatsTracker<S>>,
+    /// Subscribed observers, if any.
+    observers: Option<Observers<Grid<S>, S>>,
 }
 
 impl<S, D> MappedBuilder<S, D> {
@@ -199,13 +1573,30 @@ impl<S, D> MappedBuilder<S, D> {
     pub fn run(self, f: StepFn<S, D>) -> Automaton<S, D> {
         Automaton {
             cells: self.grid,
+            generation: 0,
+            limit_reached_at: None,
             generations_left: self.generations_limit,
             data_fn: self.data_fn,
             step_fn: f,
+            tracker: self.tracker,
+            auto_grow: self.auto_grow,
+            history: self.history,
+            cycle_check: self.cycle_check,
+            stats: self.stats,
+            observers: self.observers,
         }
     }
 
     impl_builder_misc! { Self }
+    impl_builder_options! {}
+}
+
+/// Hashes the whole grid, see [`CycleCheck`].
+fn hash_grid<S: Hash>(grid: &Grid<S>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    grid.hash(&mut hasher);
+
+    hasher.finish()
 }
 
 pub fn iter_grid<T>(grid: &[Vec<T>]) -> impl Iterator<Item = (usize, usize, &T)> {
@@ -224,10 +1615,14 @@ pub fn count_neighbors<S, F>(grid: &Grid<S>, pos: (usize, usize), radius: u8, is
 where
     F: Fn(&S) -> bool,
 {
-    #[allow(clippy::shadow_reuse)]
+    #[allow(clippy::shadow_reuse, reason = "Same value, different type")]
     let radius = radius as isize;
 
-    #[allow(clippy::indexing_slicing, clippy::arithmetic_side_effects)]
+    #[allow(
+        clippy::indexing_slicing,
+        clippy::arithmetic_side_effects,
+        reason = "`radius` fits into `isize`"
+    )]
     (-radius..=radius)
         .flat_map(|x| {
             (-radius..=radius)
@@ -246,12 +1641,14 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{Automaton, AutomatonBuilder};
-    use crate::engine::ExecutionState;
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{count_neighbors, iter_grid, Automaton, AutomatonBuilder, Grid, InitBuilder};
+    use crate::engine::{observer::Observer, ExecutionState, Pos, Rect};
 
     const DEFAULT_GRID_SIZE: (usize, usize) = (10, 10);
     const DEFAULT_INIT_FN: fn((usize, usize)) -> bool = |(x, y)| x > y;
-    const DEFAULT_STEP_FN: fn((usize, usize), bool, ()) -> bool = |(_x, _y), state, _| state;
+    const DEFAULT_STEP_FN: fn((usize, usize), bool, ()) -> bool = |(_x, _y), state, ()| state;
 
     fn default_game() -> Automaton<bool, ()> {
         AutomatonBuilder::new(DEFAULT_GRID_SIZE)
@@ -272,6 +1669,30 @@ mod tests {
         let _game = default_game();
     }
 
+    #[test]
+    fn generations_limit_after_map() {
+        let mut game = AutomatonBuilder::new(DEFAULT_GRID_SIZE)
+            .init(DEFAULT_INIT_FN)
+            .generations(1)
+            .map(|_, _, _| ())
+            .run(DEFAULT_STEP_FN);
+
+        assert_eq!(game.step(), ExecutionState::Remaining(0));
+        assert_eq!(game.step(), ExecutionState::Finished);
+    }
+
+    #[test]
+    fn grid_size() {
+        let game = AutomatonBuilder::new((3, 2))
+            .init(|(x, y)| x == y)
+            .run(DEFAULT_STEP_FN);
+
+        assert_eq!(game.grid_size(), (3, 2));
+        assert_eq!(game.cells().len(), 2);
+        assert_eq!(game.cells()[0].len(), 3);
+        assert_eq!(game.cells()[1], [false, true, false]);
+    }
+
     #[test]
     fn generations_limit() {
         let mut game = default_game();
@@ -286,4 +1707,483 @@ mod tests {
 
         assert_eq!(game.step(), ExecutionState::Infinite);
     }
+
+    fn conway(builder: InitBuilder<bool>) -> Automaton<bool, usize> {
+        builder
+            .map(|pos, _, cells| count_neighbors(cells, pos, 1, |b| *b))
+            .run(|_, is_alive, neighbors_n| {
+                if is_alive {
+                    (2..=3).contains(&neighbors_n)
+                } else {
+                    neighbors_n == 3
+                }
+            })
+    }
+
+    #[test]
+    fn change_tracking() {
+        // A glider and a blinker
+        let init = |pos| {
+            [
+                (1, 0),
+                (2, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2),
+                (7, 8),
+                (8, 8),
+                (9, 8),
+            ]
+            .contains(&pos)
+        };
+        let mut full = conway(AutomatonBuilder::new((12, 12)).init(init));
+        let mut tracked = conway(AutomatonBuilder::new((12, 12)).init(init).track_changes(1));
+
+        assert!(tracked.is_tracking_changes());
+        assert_eq!(tracked.changed_cells(), None);
+
+        for _ in 0..20_u32 {
+            full.step();
+            tracked.step();
+
+            assert_eq!(full.cells(), tracked.cells());
+        }
+
+        // Only the glider and the blinker change
+        assert!(tracked
+            .changed_cells()
+            .is_some_and(|changed| changed.len() < 20));
+    }
+
+    #[test]
+    fn resizing() {
+        let mut game = AutomatonBuilder::new((3, 2))
+            .init(|(x, y)| x == y)
+            .run(DEFAULT_STEP_FN);
+
+        assert_eq!(game.grid_size(), (3, 2));
+
+        game.pad(1, 0, 2, 1, false);
+        assert_eq!(game.grid_size(), (4, 5));
+        assert_eq!(game.cells()[1], [false, true, false, false]);
+        assert_eq!(game.cells()[2], [false, false, true, false]);
+
+        game.crop(Rect::new((1, 1), (2, 10)));
+        assert_eq!(
+            game.cells(),
+            &[[true, false], [false, true], [false, false], [false, false]]
+        );
+
+        game.resize((3, 1), true);
+        assert_eq!(game.cells(), &[[true, false, true]]);
+    }
+
+    #[test]
+    fn auto_grow() {
+        // A glider heading towards the bottom-right corner
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let mut game = conway(
+            AutomatonBuilder::new((5, 5))
+                .init(|pos| glider.contains(&pos))
+                .grow_on_edge(2, false)
+                .track_changes(1),
+        );
+
+        for _ in 0..40_u32 {
+            game.step();
+        }
+
+        // The glider is still intact
+        assert!(game.grid_size().0 > 10);
+        assert_eq!(iter_grid(game.cells()).filter(|(_, _, b)| **b).count(), 5);
+
+        // A block on the right edge only grows the grid to the right, once
+        let block = [(3, 1), (4, 1), (3, 2), (4, 2)];
+        let mut game = conway(
+            AutomatonBuilder::new((5, 5))
+                .init(|pos| block.contains(&pos))
+                .grow_on_edge(2, false),
+        );
+
+        game.run_for(3);
+        assert_eq!(game.grid_size(), (7, 5));
+        assert!(block.iter().all(|&pos| game.get(pos) == Some(&true)));
+    }
+
+    #[test]
+    fn editing() {
+        let mut full = conway(AutomatonBuilder::new((8, 8)).init(|_| false));
+        let mut tracked = conway(
+            AutomatonBuilder::new((8, 8))
+                .init(|_| false)
+                .track_changes(1),
+        );
+
+        for game in [&mut full, &mut tracked] {
+            game.step();
+
+            // A block and a blinker
+            game.fill_rect(Rect::new((0, 0), (2, 2)), true);
+            assert_eq!(game.set((5, 4), true), Some(false));
+            assert_eq!(game.set((8, 0), true), None);
+            *game.cells_mut().get_mut((5, 5)).unwrap() = true;
+            game.set((5, 6), true);
+            assert_eq!(game.get((5, 6)), Some(&true));
+            game.step();
+        }
+
+        assert_eq!(full.cells(), tracked.cells());
+        assert_eq!(tracked.get((4, 5)), Some(&true));
+        assert_eq!(tracked.get((1, 1)), Some(&true));
+
+        tracked.clear(false);
+        assert!(!iter_grid(tracked.cells()).any(|(_, _, b)| *b));
+    }
+
+    #[test]
+    fn history() {
+        let mut game = conway(
+            AutomatonBuilder::new((8, 8))
+                .init(|(x, y)| x == 1 && y < 3)
+                .generations(10)
+                .history(usize::MAX),
+        );
+        let initial = game.cells().clone();
+
+        game.step();
+        let blinker = game.cells().clone();
+        game.step();
+        game.set((6, 6), true);
+
+        assert!(game.step_back());
+        assert_eq!(game.cells(), &blinker);
+        assert!(game.step_back());
+        assert_eq!(game.cells(), &initial);
+        assert!(!game.step_back());
+        assert_eq!(game.step(), ExecutionState::Remaining(9));
+
+        assert!(game.seek(4));
+        assert_eq!(game.cells(), &initial);
+        assert!(game.seek(3));
+        assert_eq!(game.cells(), &blinker);
+
+        // Going back doesn't extend the generation limit past its end
+        let mut game = conway(
+            AutomatonBuilder::new((4, 4))
+                .init(|_| false)
+                .generations(2)
+                .history(usize::MAX),
+        );
+
+        for _ in 0..4_u8 {
+            game.step();
+        }
+
+        assert!(game.seek(1));
+        assert_eq!(game.step(), ExecutionState::Remaining(0));
+        assert_eq!(game.step(), ExecutionState::Finished);
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut game = conway(
+            AutomatonBuilder::new((6, 6))
+                .init(|(x, y)| x == 1 && y < 3)
+                .generations(3)
+                .track_changes(1)
+                .grow_on_edge(1, false)
+                .detect_cycles(4, false)
+                .track_stats(false),
+        );
+
+        game.step();
+
+        let checkpoint = game.snapshot();
+        let mut restored = Automaton::restore(checkpoint.clone(), game.step_fn, |pos, _, cells| {
+            count_neighbors(cells, pos, 1, |b| *b)
+        });
+
+        assert_eq!(restored.snapshot(), checkpoint);
+        assert_eq!(game.step(), restored.step());
+        assert_eq!(game.cells(), restored.cells());
+
+        // Cycle detection and statistics are restored as well
+        assert_eq!(restored.max_period(), Some(4));
+        assert_eq!(restored.stats(), game.stats());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let mut game = conway(
+            AutomatonBuilder::new((4, 4))
+                .init(|(x, y)| x == y)
+                .history(1024),
+        );
+        game.step();
+
+        let checkpoint = game.snapshot();
+        let json = serde_json::to_string(&checkpoint).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<super::Checkpoint<bool>>(&json).unwrap(),
+            checkpoint
+        );
+    }
+
+    #[test]
+    fn cycle_detection() {
+        let blinker = |(x, y)| x == 2 && (1..=3).contains(&y);
+        let mut game = conway(
+            AutomatonBuilder::new((5, 5))
+                .init(blinker)
+                .detect_cycles(4, false),
+        );
+
+        assert_eq!(game.max_period(), Some(4));
+        assert_eq!(game.step(), ExecutionState::Infinite);
+        assert_eq!(game.step(), ExecutionState::Stabilized { period: 2 });
+
+        // Edits restart the detection
+        game.set((0, 0), true);
+        assert_eq!(game.step(), ExecutionState::Infinite);
+
+        game.clear(false);
+        assert_eq!(game.step(), ExecutionState::Extinct);
+
+        // Restored automata detect cycles as well
+        let game = conway(
+            AutomatonBuilder::new((5, 5))
+                .init(blinker)
+                .detect_cycles(4, false),
+        );
+        let mut restored = Automaton::restore(game.snapshot(), game.step_fn, |pos, _, cells| {
+            count_neighbors(cells, pos, 1, |b| *b)
+        });
+
+        assert_eq!(restored.step(), ExecutionState::Infinite);
+        assert_eq!(restored.step(), ExecutionState::Stabilized { period: 2 });
+
+        let block = |(x, y): (usize, usize)| x < 2 && y < 2;
+        let mut game = conway(
+            AutomatonBuilder::new((4, 4))
+                .init(block)
+                .generations(3)
+                .detect_cycles(1, false),
+        );
+
+        assert_eq!(game.step(), ExecutionState::Stabilized { period: 1 });
+        assert_eq!(game.step(), ExecutionState::Stabilized { period: 1 });
+        assert_eq!(game.step(), ExecutionState::Stabilized { period: 1 });
+        assert_eq!(game.step(), ExecutionState::Finished);
+    }
+
+    #[test]
+    fn stats() {
+        let glider = |(x, y)| matches!((x, y), (1, 0) | (2, 1) | (0..=2, 2));
+        let builder = AutomatonBuilder::new((8, 8))
+            .init(glider)
+            .track_stats(false);
+        let mut game = conway(builder);
+        let mut tracked = conway(
+            AutomatonBuilder::new((8, 8))
+                .init(glider)
+                .track_changes(1)
+                .track_stats(false),
+        );
+
+        let stats = game.stats().unwrap();
+        assert_eq!(stats.population, 5);
+        assert_eq!(stats.bounding_box, Some(Rect::new((0, 0), (3, 3))));
+        assert_eq!(stats.states.get(&true), Some(&5));
+
+        for _ in 0..4_u8 {
+            game.step();
+            tracked.step();
+
+            let stats = game.stats().unwrap();
+            assert_eq!(stats.population, 5);
+            assert_eq!(stats.births, stats.deaths);
+            assert_eq!(tracked.stats(), Some(stats));
+        }
+
+        // The glider moved by (1, 1)
+        assert_eq!(game.stats().unwrap().births, 2);
+        assert_eq!(
+            game.stats().unwrap().bounding_box,
+            Some(Rect::new((1, 1), (3, 3)))
+        );
+
+        game.clear(false);
+        game.step();
+        assert_eq!(game.stats().unwrap().population, 0);
+        assert_eq!(game.stats().unwrap().bounding_box, None);
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        steps: Vec<ExecutionState>,
+        changes: Vec<(Pos, bool)>,
+    }
+
+    impl Observer<Grid<bool>, bool> for Recorder {
+        fn before_step(&mut self, _cells: &Grid<bool>) {
+            self.changes.clear();
+        }
+
+        fn after_step(&mut self, _cells: &Grid<bool>, state: ExecutionState) {
+            self.steps.push(state);
+        }
+
+        fn cell_changed(&mut self, pos: Pos, old: Option<&bool>, new: Option<&bool>) {
+            assert_eq!(old.copied(), new.map(|is_alive| !is_alive));
+
+            if let Some(is_alive) = new {
+                self.changes.push((pos, *is_alive));
+            }
+        }
+
+        fn observes_cells(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn observers() {
+        let blinker = |(x, y)| x == 1 && y < 3;
+        let recorder = Rc::new(RefCell::new(Recorder::default()));
+        let tracked_recorder = Rc::new(RefCell::new(Recorder::default()));
+        let mut game = conway(
+            AutomatonBuilder::new((3, 3))
+                .init(blinker)
+                .generations(2)
+                .observe(Rc::clone(&recorder)),
+        );
+        let mut tracked = conway(AutomatonBuilder::new((3, 3)).init(blinker).track_changes(1));
+        tracked.observe(Rc::clone(&tracked_recorder));
+
+        for _ in 0..2_u8 {
+            game.step();
+            tracked.step();
+
+            let mut changes = recorder.borrow().changes.clone();
+            let mut tracked_changes = tracked_recorder.borrow().changes.clone();
+            changes.sort_unstable();
+            tracked_changes.sort_unstable();
+
+            assert_eq!(changes.len(), 4);
+            assert_eq!(changes, tracked_changes);
+        }
+
+        assert_eq!(
+            recorder.borrow().steps,
+            [ExecutionState::Remaining(1), ExecutionState::Remaining(0)]
+        );
+
+        game.clear_observers();
+        game.step();
+        assert_eq!(recorder.borrow().steps.len(), 2);
+    }
+
+    #[test]
+    fn running() {
+        let blinker = |(x, y)| x == 2 && (1..=3).contains(&y);
+        let mut game = conway(AutomatonBuilder::new((5, 5)).init(blinker).generations(5));
+
+        assert_eq!(game.run_for(3), ExecutionState::Remaining(2));
+        assert_eq!(game.run_for(10), ExecutionState::Remaining(0));
+        assert_eq!(game.run_for(1), ExecutionState::Finished);
+
+        let mut game = conway(AutomatonBuilder::new((5, 5)).init(blinker).generations(4));
+        let mut generations = game.generations();
+        let mut alive = Vec::new();
+
+        while let Some((_, cells)) = generations.next() {
+            alive.push(cells[2][1]);
+        }
+
+        assert_eq!(alive, [true, false, true, false]);
+
+        let mut game = conway(AutomatonBuilder::new((5, 5)).init(blinker));
+        assert_eq!(
+            game.run_until(|game| game.get((1, 2)) == Some(&true)),
+            ExecutionState::Infinite
+        );
+        assert_eq!(game.get((2, 1)), Some(&false));
+
+        // The glider turns into a block in the corner
+        let glider = |(x, y)| matches!((x, y), (1, 0) | (2, 1) | (0..=2, 2));
+        let mut game = conway(AutomatonBuilder::new((6, 6)).init(glider));
+        let mut tracked = conway(AutomatonBuilder::new((6, 6)).init(glider).track_changes(1));
+
+        assert_eq!(
+            game.run_while_changing(),
+            ExecutionState::Stabilized { period: 1 }
+        );
+        assert_eq!(
+            tracked.run_while_changing(),
+            ExecutionState::Stabilized { period: 1 }
+        );
+        assert_eq!(game.cells(), tracked.cells());
+    }
+
+    #[test]
+    fn generation_counter() {
+        // Alive on even generations
+        let mut game = AutomatonBuilder::new((2, 2))
+            .init(|_| false)
+            .map_timed(|_, _, _, generation| generation)
+            .history(1024)
+            .run(|_, _, generation| generation % 2 == 1);
+
+        assert_eq!(game.generation(), 0);
+
+        game.run_for(3);
+        assert_eq!(game.generation(), 3);
+        assert_eq!(game.get((0, 0)), Some(&false));
+
+        assert!(game.step_back());
+        assert_eq!(game.generation(), 2);
+        assert_eq!(game.get((0, 0)), Some(&true));
+
+        assert!(game.seek(5));
+        assert_eq!(game.generation(), 5);
+
+        let checkpoint = game.snapshot();
+        let mut restored = Automaton::restore_timed(checkpoint, game.step_fn, |_, _, _, n| n);
+
+        assert_eq!(restored.generation(), 5);
+        assert_eq!(restored.history().unwrap().latest_generation(), 5);
+        assert_eq!(restored.step(), game.step());
+        assert_eq!(restored.cells(), game.cells());
+
+        // The recorded history is restored as well
+        assert!(restored.seek(2));
+        assert_eq!(restored.get((0, 0)), Some(&true));
+    }
+
+    #[test]
+    fn automaton_trait() {
+        use crate::{engine::Automaton as _, frontend::render_automaton_str};
+
+        let mut game = conway(
+            AutomatonBuilder::new((3, 3))
+                .init(|(x, y)| x == 1 && y < 3)
+                .generations(2),
+        );
+        let render = |game: &Automaton<bool, usize>| {
+            let area = crate::engine::Automaton::bounding_box(game).unwrap();
+            render_automaton_str(game, area, |state| if *state.unwrap() { '#' } else { '.' })
+        };
+
+        assert_eq!(render(&game), ".#.\n.#.\n.#.\n");
+        assert_eq!(
+            crate::engine::Automaton::step(&mut game),
+            ExecutionState::Remaining(1)
+        );
+        assert_eq!(render(&game), "...\n###\n...\n");
+        assert_eq!(game.live_cells(|is_alive| *is_alive).count(), 3);
+        assert_eq!(game.iter_cells().count(), 9);
+        assert_eq!(crate::engine::Automaton::generations_left(&game), Some(1));
+    }
 }
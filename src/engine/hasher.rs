@@ -0,0 +1,118 @@
+//! A hasher tuned for grid positions.
+//!
+//! Live cells usually form dense clusters, so the positions hashed by a
+//! [`life_like::Grid`](super::life_like::Grid) differ mostly in the low bits
+//! of their coordinates. [`PosHasher`] packs both coordinates into a single
+//! word (which can't collide as long as they fit in 32 bits) and mixes it
+//! with one multiplication, spreading those low bits over the whole hash.
+//!
+//! Signed coordinates are zigzag-encoded first (`0, -1, 1, -2, ...` become
+//! `0, 1, 2, 3, ...`), so that clusters around the origin pack as well.
+//!
+//! # Examples
+//! ```rust
+//! use lifers::engine::{
+//!     hasher::PosBuildHasher,
+//!     life_like::{count_neighbors, AutomatonBuilder, Grid},
+//! };
+//!
+//! let mut game = AutomatonBuilder::new(1)
+//!     .init_with_hasher(|| {
+//!         [((1, 0), ()), ((1, 1), ()), ((1, 2), ())]
+//!             .into_iter()
+//!             .collect::<Grid<(), PosBuildHasher>>()
+//!     })
+//!     .map(|pos, _, cells| count_neighbors(pos, 1, cells))
+//!     .run(|_, state, neighbors_n| match state {
+//!         Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+//!         None => (neighbors_n == 3).then_some(()),
+//!     });
+//!
+//! game.step();
+//! assert!(game.get((0, 1)).is_some());
+//! ```
+
+use std::hash::{BuildHasher, Hasher};
+
+/// Odd multiplier of the finalizer (`2^64 / φ`).
+const MULTIPLIER: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A fast, non-cryptographic hasher for [`Pos`](super::Pos)itions.
+///
+/// Other keys are supported too, but aren't hashed as well as by general
+/// purpose hashers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PosHasher {
+    hash: u64,
+}
+
+impl Hasher for PosHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0_u8; 8];
+
+            for (byte, x) in word.iter_mut().zip(chunk) {
+                *byte = *x;
+            }
+
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.hash = self.hash.rotate_left(32) ^ n;
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+
+    fn write_i64(&mut self, n: i64) {
+        self.write_u64((n.wrapping_shl(1) ^ n.wrapping_shr(63)).cast_unsigned());
+    }
+
+    fn write_isize(&mut self, n: isize) {
+        self.write_i64(n as i64);
+    }
+
+    fn finish(&self) -> u64 {
+        let hash = self.hash.wrapping_mul(MULTIPLIER);
+
+        hash ^ hash.wrapping_shr(32)
+    }
+}
+
+/// Creates [`PosHasher`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PosBuildHasher;
+
+impl BuildHasher for PosBuildHasher {
+    type Hasher = PosHasher;
+
+    fn build_hasher(&self) -> PosHasher {
+        PosHasher::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, hash::BuildHasher};
+
+    use itertools::Itertools;
+
+    use super::PosBuildHasher;
+    use crate::engine::Pos;
+
+    #[test]
+    fn clusters() {
+        let hashes: HashSet<u64> = (-128..128_i64)
+            .cartesian_product(-128..128_i64)
+            .map(|pos: Pos<i64>| PosBuildHasher.hash_one(pos))
+            .collect();
+        let buckets: HashSet<u64> = hashes.iter().map(|hash| hash & 0xffff).collect();
+
+        assert_eq!(hashes.len(), 256 * 256);
+        // A random hash would use about 63% of the 2^16 buckets
+        assert!(buckets.len() > 256 * 150);
+    }
+}
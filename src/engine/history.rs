@@ -0,0 +1,664 @@
+//! Bounded undo history for automata.
+//!
+//! A [`History`] stores full copies of the grid (keyframes) every
+//! [`DEFAULT_KEYFRAME_INTERVAL`] generations and only the changed cells
+//! (diffs) for the generations in between. Any recorded generation is
+//! reconstructed by applying the diffs on top of the closest older keyframe.
+//!
+//! When the history exceeds its memory budget, it is thinned out starting
+//! from the oldest generations: first the diffs between keyframes are
+//! dropped, then every other keyframe. The most recent generations are thus
+//! always available, while older ones become increasingly sparse.
+//!
+//! The recorded generations can be saved (e.g. as part of an automaton's
+//! checkpoint) and restored later, see [`SavedHistory`].
+
+use std::{collections::VecDeque, hash::BuildHasher, mem};
+
+use itertools::Itertools;
+
+use crate::engine::{generic, life_like, Pos};
+
+/// Number of generations between two keyframes.
+pub const DEFAULT_KEYFRAME_INTERVAL: u64 = 64;
+
+/// Settings of a [`History`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoryConfig {
+    /// Memory budget in bytes.
+    pub budget: usize,
+    /// Number of generations between two keyframes.
+    pub keyframe_interval: u64,
+}
+
+impl HistoryConfig {
+    /// Returns the settings with the given budget and the default keyframe
+    /// interval.
+    pub const fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            keyframe_interval: DEFAULT_KEYFRAME_INTERVAL,
+        }
+    }
+}
+
+/// A grid that can be recorded in a [`History`].
+pub trait Snapshot: Sized {
+    /// Changes that turn one snapshot into another.
+    type Diff;
+
+    /// Returns a copy of the grid.
+    fn snapshot(&self) -> Self;
+
+    /// Returns the changes that turn `self` into `newer`, or `None` if they
+    /// can't be represented as a diff (e.g. the grid was resized).
+    fn diff(&self, newer: &Self) -> Option<Self::Diff>;
+
+    /// Applies the changes returned by [`diff()`](Self::diff).
+    fn apply(&mut self, diff: &Self::Diff);
+
+    /// Returns the approximate number of bytes used by the grid.
+    fn memory_usage(&self) -> usize;
+
+    /// Returns the approximate number of bytes used by a diff.
+    fn diff_memory_usage(diff: &Self::Diff) -> usize;
+}
+
+impl<S: Clone + PartialEq> Snapshot for generic::Grid<S> {
+    type Diff = Vec<(Pos, S)>;
+
+    fn snapshot(&self) -> Self {
+        self.clone()
+    }
+
+    fn diff(&self, newer: &Self) -> Option<Self::Diff> {
+        let same_size = self.len() == newer.len()
+            && self
+                .iter()
+                .zip(newer)
+                .all(|(xs, new_xs)| xs.len() == new_xs.len());
+
+        same_size.then(|| {
+            generic::iter_grid(newer)
+                .filter(|(x, y, state)| self[*y][*x] != **state)
+                .map(|(x, y, state)| ((x, y), state.clone()))
+                .collect()
+        })
+    }
+
+    fn apply(&mut self, diff: &Self::Diff) {
+        for ((x, y), state) in diff {
+            self[*y][*x].clone_from(state);
+        }
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.iter()
+            .map(|xs| mem::size_of_val(xs.as_slice()))
+            .fold(0, usize::saturating_add)
+    }
+
+    fn diff_memory_usage(diff: &Self::Diff) -> usize {
+        mem::size_of_val(diff.as_slice())
+    }
+}
+
+#[allow(
+    clippy::implicit_hasher,
+    reason = "False positive, `Grid` is already generic over its hasher"
+)]
+impl<S: Clone + PartialEq, H: BuildHasher + Clone> Snapshot for life_like::Grid<S, H> {
+    type Diff = Vec<(Pos<i64>, Option<S>)>;
+
+    fn snapshot(&self) -> Self {
+        self.clone()
+    }
+
+    fn diff(&self, newer: &Self) -> Option<Self::Diff> {
+        let born = newer
+            .iter()
+            .filter(|(pos, state)| self.get(pos) != Some(state))
+            .map(|(pos, state)| (*pos, Some(state.clone())));
+        let died = self
+            .keys()
+            .filter(|pos| !newer.contains_key(pos))
+            .map(|pos| (*pos, None));
+
+        Some(born.chain(died).collect())
+    }
+
+    fn apply(&mut self, diff: &Self::Diff) {
+        for (pos, state) in diff {
+            match state {
+                Some(s) => self.insert(*pos, s.clone()),
+                None => self.remove(pos),
+            };
+        }
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.len().saturating_mul(mem::size_of::<(Pos<i64>, S)>())
+    }
+
+    fn diff_memory_usage(diff: &Self::Diff) -> usize {
+        mem::size_of_val(diff.as_slice())
+    }
+}
+
+/// A recorded generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Frame<G, D> {
+    /// A full copy of the grid.
+    Keyframe(G),
+    /// The changes since the previous recorded generation.
+    Diff(D),
+}
+
+/// The recorded generations of a [`History`] along with its settings.
+///
+/// Returned by [`History::save()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SavedHistory<G, D> {
+    pub config: HistoryConfig,
+    /// Recorded generations, oldest first. The oldest one has to be a
+    /// keyframe.
+    pub frames: Vec<(u64, Frame<G, D>)>,
+    /// `true` if the grid was edited after the newest generation was
+    /// recorded.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_dirty: bool,
+}
+
+struct Entry<G, D> {
+    generation: u64,
+    frame: Frame<G, D>,
+    /// Approximate number of bytes used by `frame`.
+    size: usize,
+}
+
+/// Recorded past generations of an automaton.
+///
+/// See the [module-level documentation](self) for details.
+pub struct History<G, D> {
+    /// Recorded generations, oldest first.
+    entries: VecDeque<Entry<G, D>>,
+    /// The grid at the newest recorded generation.
+    latest: G,
+    /// Approximate number of bytes used by all entries and `latest`.
+    usage: usize,
+    /// `true` if the grid was edited after the newest generation was
+    /// recorded.
+    dirty: bool,
+    /// Number of diffs since the last keyframe.
+    since_keyframe: u64,
+    keyframe_interval: u64,
+    /// Memory budget in bytes.
+    budget: usize,
+    ops: SnapshotOps<G, D>,
+}
+
+/// Type-erased [`Snapshot`] methods, so that the automata can record
+/// history without requiring any trait bounds on their cell states.
+struct SnapshotOps<G, D> {
+    snapshot: fn(&G) -> G,
+    diff: fn(&G, &G) -> Option<D>,
+    apply: fn(&mut G, &D),
+    memory_usage: fn(&G) -> usize,
+    diff_memory_usage: fn(&D) -> usize,
+}
+
+impl<G: Snapshot> History<G, G::Diff> {
+    /// Creates a history starting at the given grid, which is considered to
+    /// be generation `generation`.
+    ///
+    /// Once the recorded generations take up more than `budget` bytes, the
+    /// history is thinned out.
+    pub fn new(grid: &G, generation: u64, budget: usize) -> Self {
+        Self::with_config(grid, generation, HistoryConfig::new(budget))
+    }
+
+    /// Like [`new()`](Self::new), but with custom settings.
+    pub fn with_config(grid: &G, generation: u64, config: HistoryConfig) -> Self {
+        let size = grid.memory_usage();
+
+        Self {
+            entries: VecDeque::from([Entry {
+                generation,
+                frame: Frame::Keyframe(grid.snapshot()),
+                size,
+            }]),
+            latest: grid.snapshot(),
+            usage: size.saturating_mul(2),
+            dirty: false,
+            since_keyframe: 0,
+            keyframe_interval: config.keyframe_interval.max(1),
+            budget: config.budget,
+            ops: SnapshotOps::new(),
+        }
+    }
+
+    /// Restores a history saved with [`save()`](History::save).
+    ///
+    /// Returns `None` if there are no generations, the oldest one isn't a
+    /// keyframe or the generations aren't in ascending order.
+    pub fn restore(saved: SavedHistory<G, G::Diff>) -> Option<Self> {
+        let SavedHistory {
+            config,
+            frames,
+            is_dirty,
+        } = saved;
+        let is_valid = matches!(frames.first(), Some((_, Frame::Keyframe(_))))
+            && frames.iter().tuple_windows().all(|((a, _), (b, _))| a < b);
+
+        if !is_valid {
+            return None;
+        }
+
+        let ops = SnapshotOps::<G, G::Diff>::new();
+        let entries: VecDeque<_> = frames
+            .into_iter()
+            .map(|(generation, frame)| Entry {
+                generation,
+                size: ops.frame_memory_usage(&frame),
+                frame,
+            })
+            .collect();
+        let since_keyframe = entries
+            .iter()
+            .rev()
+            .take_while(|e| matches!(e.frame, Frame::Diff(_)))
+            .count() as u64;
+        let mut history = Self {
+            latest: ops.reconstruct(&entries, entries.len().saturating_sub(1)),
+            entries,
+            usage: 0,
+            dirty: is_dirty,
+            since_keyframe,
+            keyframe_interval: config.keyframe_interval.max(1),
+            budget: config.budget,
+            ops,
+        };
+        history.usage = history
+            .entries
+            .iter()
+            .map(|e| e.size)
+            .fold(G::memory_usage(&history.latest), usize::saturating_add);
+        history.thin_out();
+
+        Some(history)
+    }
+}
+
+impl<G: Snapshot> SnapshotOps<G, G::Diff> {
+    const fn new() -> Self {
+        Self {
+            snapshot: G::snapshot,
+            diff: G::diff,
+            apply: G::apply,
+            memory_usage: G::memory_usage,
+            diff_memory_usage: G::diff_memory_usage,
+        }
+    }
+}
+
+impl<G, D> SnapshotOps<G, D> {
+    fn frame_memory_usage(&self, frame: &Frame<G, D>) -> usize {
+        match frame {
+            Frame::Keyframe(grid) => (self.memory_usage)(grid),
+            Frame::Diff(diff) => (self.diff_memory_usage)(diff),
+        }
+    }
+
+    /// Rebuilds the grid stored at `entries[idx]`.
+    fn reconstruct(&self, entries: &VecDeque<Entry<G, D>>, idx: usize) -> G {
+        let keyframe_idx = entries
+            .range(..=idx)
+            .rposition(|e| matches!(e.frame, Frame::Keyframe(_)))
+            .unwrap_or_default();
+        let mut frames = entries.range(keyframe_idx..=idx).map(|e| &e.frame);
+
+        let mut grid = match frames.next() {
+            Some(Frame::Keyframe(grid)) => (self.snapshot)(grid),
+            // NOTE: The oldest entry is always a keyframe
+            _ => unreachable!(),
+        };
+
+        for frame in frames {
+            if let Frame::Diff(diff) = frame {
+                (self.apply)(&mut grid, diff);
+            }
+        }
+
+        grid
+    }
+}
+
+impl<G, D> History<G, D> {
+    /// Sets the number of generations between two keyframes.
+    ///
+    /// Smaller intervals make seeking faster at the cost of memory.
+    pub fn set_keyframe_interval(&mut self, interval: u64) {
+        self.keyframe_interval = interval.max(1);
+    }
+
+    /// Returns the recorded generations along with the settings, which can
+    /// be used to [`restore()`](History::restore) the history later.
+    pub fn save(&self) -> SavedHistory<G, D>
+    where
+        D: Clone,
+    {
+        SavedHistory {
+            config: self.config(),
+            frames: self
+                .entries
+                .iter()
+                .map(|e| {
+                    let frame = match &e.frame {
+                        Frame::Keyframe(grid) => Frame::Keyframe((self.ops.snapshot)(grid)),
+                        Frame::Diff(diff) => Frame::Diff(diff.clone()),
+                    };
+
+                    (e.generation, frame)
+                })
+                .collect(),
+            is_dirty: self.dirty,
+        }
+    }
+
+    /// Returns the history's settings.
+    pub const fn config(&self) -> HistoryConfig {
+        HistoryConfig {
+            budget: self.budget,
+            keyframe_interval: self.keyframe_interval,
+        }
+    }
+
+    /// Returns the generation of the newest recorded grid.
+    pub fn latest_generation(&self) -> u64 {
+        self.entries.back().map_or(0, |e| e.generation)
+    }
+
+    /// Returns the generation of the oldest recorded grid.
+    pub fn oldest_generation(&self) -> u64 {
+        self.entries.front().map_or(0, |e| e.generation)
+    }
+
+    /// Returns an iterator over all generations that can be restored,
+    /// oldest first.
+    pub fn generations(&self) -> impl Iterator<Item = u64> + '_ {
+        self.entries.iter().map(|e| e.generation)
+    }
+
+    /// Returns `true` if the given generation can be restored.
+    pub fn contains(&self, generation: u64) -> bool {
+        self.position(generation).is_some()
+    }
+
+    /// Returns the approximate number of bytes used by the history.
+    pub const fn memory_usage(&self) -> usize {
+        self.usage
+    }
+
+    /// Returns the memory budget in bytes.
+    pub const fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Sets the memory budget in bytes, thinning out the history if needed.
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.thin_out();
+    }
+
+    /// Records the next generation.
+    pub(crate) fn record(&mut self, grid: &G) {
+        let generation = self.latest_generation().saturating_add(1);
+        let diff = (self.since_keyframe.saturating_add(1) < self.keyframe_interval)
+            .then(|| (self.ops.diff)(&self.latest, grid))
+            .flatten();
+
+        let frame = if let Some(diff) = diff {
+            self.since_keyframe = self.since_keyframe.saturating_add(1);
+            Frame::Diff(diff)
+        } else {
+            self.since_keyframe = 0;
+            Frame::Keyframe((self.ops.snapshot)(grid))
+        };
+        let size = self.ops.frame_memory_usage(&frame);
+
+        self.usage = self.usage.saturating_add(size);
+        self.entries.push_back(Entry {
+            generation,
+            frame,
+            size,
+        });
+        self.set_latest(grid);
+        self.dirty = false;
+        self.thin_out();
+    }
+
+    /// Marks the newest generation as edited. It is re-recorded on the next
+    /// call to [`sync()`](Self::sync).
+    pub(crate) const fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Replaces the newest generation with `grid` if it was edited.
+    pub(crate) fn sync(&mut self, grid: &G) {
+        if !self.dirty {
+            return;
+        }
+
+        if let Some(entry) = self.entries.back_mut() {
+            let size = (self.ops.memory_usage)(grid);
+
+            entry.frame = Frame::Keyframe((self.ops.snapshot)(grid));
+            self.usage = self.usage.saturating_sub(entry.size).saturating_add(size);
+            entry.size = size;
+        }
+
+        self.set_latest(grid);
+        self.since_keyframe = 0;
+        self.dirty = false;
+        self.thin_out();
+    }
+
+    /// Restores the grid at `generation`, discarding all newer generations.
+    ///
+    /// Returns `None` if the generation is not recorded.
+    pub(crate) fn rewind(&mut self, generation: u64) -> Option<G> {
+        let idx = self.position(generation)?;
+        let grid = self.ops.reconstruct(&self.entries, idx);
+
+        for entry in self.entries.drain(idx.saturating_add(1)..) {
+            self.usage = self.usage.saturating_sub(entry.size);
+        }
+
+        self.since_keyframe = self
+            .entries
+            .iter()
+            .rev()
+            .take_while(|e| matches!(e.frame, Frame::Diff(_)))
+            .count() as u64;
+        self.set_latest(&grid);
+
+        Some(grid)
+    }
+
+    /// Returns the newest recorded generation older than the latest one.
+    pub(crate) fn previous_generation(&self) -> Option<u64> {
+        self.entries
+            .len()
+            .checked_sub(2)
+            .and_then(|idx| self.entries.get(idx))
+            .map(|e| e.generation)
+    }
+
+    fn position(&self, generation: u64) -> Option<usize> {
+        self.entries
+            .binary_search_by_key(&generation, |e| e.generation)
+            .ok()
+    }
+
+    /// Replaces the newest recorded grid, keeping the memory usage up to
+    /// date.
+    fn set_latest(&mut self, grid: &G) {
+        self.usage = self
+            .usage
+            .saturating_sub((self.ops.memory_usage)(&self.latest))
+            .saturating_add((self.ops.memory_usage)(grid));
+        self.latest = (self.ops.snapshot)(grid);
+    }
+
+    /// Drops old generations until the history fits into its budget.
+    fn thin_out(&mut self) {
+        while self.usage > self.budget && self.thin_out_once() {}
+    }
+
+    /// Drops some of the oldest generations, returning `false` if nothing
+    /// can be dropped without losing the newest keyframe.
+    fn thin_out_once(&mut self) -> bool {
+        let is_keyframe = |e: &Entry<G, D>| matches!(e.frame, Frame::Keyframe(_));
+        let Some(last_keyframe) = self.entries.iter().rposition(is_keyframe) else {
+            return false;
+        };
+
+        // Drop the diffs following the oldest keyframe that has any
+        if let Some(start) =
+            (1..last_keyframe).find(|idx| self.entries.get(*idx).is_some_and(|e| !is_keyframe(e)))
+        {
+            let end = (start..last_keyframe)
+                .find(|idx| self.entries.get(*idx).is_some_and(is_keyframe))
+                .unwrap_or(last_keyframe);
+
+            for entry in self.entries.drain(start..end) {
+                self.usage = self.usage.saturating_sub(entry.size);
+            }
+
+            return true;
+        }
+
+        // Only keyframes are left before the newest one, drop every other
+        match last_keyframe {
+            0 => false,
+            1 | 2 => {
+                if let Some(entry) = self.entries.pop_front() {
+                    self.usage = self.usage.saturating_sub(entry.size);
+                }
+
+                true
+            }
+            _ => {
+                let (mut idx, mut usage) = (0_usize, self.usage);
+
+                self.entries.retain(|entry| {
+                    let keep = idx >= last_keyframe || idx.is_multiple_of(2);
+                    idx = idx.wrapping_add(1);
+
+                    if !keep {
+                        usage = usage.saturating_sub(entry.size);
+                    }
+
+                    keep
+                });
+                self.usage = usage;
+
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{History, Snapshot};
+
+    type Grid = Vec<Vec<bool>>;
+
+    /// Checks that the running memory usage matches a full recount.
+    fn assert_usage(history: &History<Grid, <Grid as Snapshot>::Diff>) {
+        let usage = history
+            .entries
+            .iter()
+            .map(|e| history.ops.frame_memory_usage(&e.frame))
+            .fold(history.latest.memory_usage(), usize::saturating_add);
+
+        assert_eq!(history.memory_usage(), usage);
+    }
+
+    #[test]
+    fn rewind_and_thin_out() {
+        let grid = |n: usize| vec![(0..16).map(|x| x < n).collect::<Vec<_>>()];
+        let mut history = History::new(&grid(0), 0, usize::MAX);
+
+        history.set_keyframe_interval(4);
+
+        for n in 1..=16 {
+            history.record(&grid(n));
+        }
+
+        assert_eq!(history.latest_generation(), 16);
+        assert_usage(&history);
+        assert_eq!(history.rewind(5), Some(grid(5)));
+        assert_eq!(history.latest_generation(), 5);
+        assert_eq!(history.previous_generation(), Some(4));
+        assert_usage(&history);
+
+        for n in 6..=14 {
+            history.record(&grid(n));
+        }
+
+        // Only the last keyframe and its diffs have to be kept
+        history.set_budget(0);
+
+        assert_eq!(history.oldest_generation(), 12);
+        assert_eq!(history.rewind(13), Some(grid(13)));
+        assert!(!history.contains(11));
+        assert_usage(&history);
+
+        history.mark_dirty();
+        history.sync(&grid(0));
+        assert_usage(&history);
+        history.set_budget(usize::MAX);
+
+        for n in 1..=10 {
+            history.record(&grid(n));
+        }
+
+        history.set_budget(history.memory_usage() / 2);
+        assert!(history.memory_usage() <= history.budget());
+        assert_eq!(history.rewind(22), Some(grid(9)));
+        assert_usage(&history);
+    }
+
+    #[test]
+    fn save_and_restore() {
+        let grid = |n: usize| vec![(0..8).map(|x| x < n).collect::<Vec<_>>()];
+        let mut history = History::new(&grid(0), 3, 4096);
+
+        history.set_keyframe_interval(3);
+
+        for n in 1..=7 {
+            history.record(&grid(n));
+        }
+
+        history.mark_dirty();
+
+        let saved = history.save();
+        let mut restored = History::restore(saved.clone()).unwrap();
+
+        assert_eq!(restored.save(), saved);
+        assert_eq!(restored.memory_usage(), history.memory_usage());
+        assert_eq!(restored.rewind(5), Some(grid(2)));
+
+        // The oldest generation has to be a keyframe
+        let mut invalid = saved;
+        invalid.frames.remove(0);
+
+        assert!(History::restore(invalid).is_none());
+    }
+}
@@ -5,6 +5,9 @@
 //! frontend) on the fly, and not worry about overflowing grid
 //! borders.
 //!
+//! Cells have signed ([`i64`]) coordinates, so patterns can move in
+//! every direction from the origin.
+//!
 //! # Limitations
 //! This struct can only model automata where the cells that are dead
 //! can only "resurrect" when they are in a radius of a cell that is
@@ -12,6 +15,8 @@
 //!
 //! This makes it similar to how Life-like automata operate, hence the
 //! name.
+//! Rules with B0 can be run by the [`background`](super::background)
+//! engine instead.
 //!
 //! Because of this design cells are represented as an [`Option<S>`],
 //! where dead cells are `None` and alive cells are `Some(s)`
@@ -22,22 +27,38 @@
 //! of "alive" cells**.
 
 use itertools::Itertools;
-use rustc_hash::FxHashMap;
-use std::collections::HashMap;
+use rustc_hash::FxBuildHasher;
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{BuildHasher, DefaultHasher, Hash, Hasher},
+    ops::{Deref, DerefMut},
+};
 
 use crate::{
-    engine::{ExecutionState, Pos},
+    analysis::{apgcode, Census, SplitOptions},
+    engine::{
+        self, convert,
+        cycle::CycleDetector,
+        generic,
+        history::{History, SavedHistory},
+        observer::{Observer, Observers},
+        ExecutionState, Pos, Rect, Stats,
+    },
     impl_builder_misc,
 };
 
-// TODO:
-// - Generalize the `Grid` to allow any `BuildHasher`
-// - Implement custom Hasher for performance
-// - Bench
-
-pub type Grid<S> = FxHashMap<Pos, S>;
-pub type DataFn<S, D> = fn(Pos, Option<&S>, &Grid<S>) -> D;
-pub type StepFn<S, D> = fn(Pos, Option<S>, D) -> Option<S>;
+/// The live cells, hashed with `H` (see [`PosBuildHasher`] for a hasher
+/// tuned for positions).
+///
+/// [`PosBuildHasher`]: crate::engine::hasher::PosBuildHasher
+pub type Grid<S, H = FxBuildHasher> = HashMap<Pos<i64>, S, H>;
+pub type DataFn<S, D, H = FxBuildHasher> = fn(Pos<i64>, Option<&S>, &Grid<S, H>) -> D;
+pub type TimedDataFn<S, D, H = FxBuildHasher> = fn(Pos<i64>, Option<&S>, &Grid<S, H>, u64) -> D;
+pub type StepFn<S, D> = fn(Pos<i64>, Option<S>, D) -> Option<S>;
+pub type GridHistory<S, H = FxBuildHasher> = History<Grid<S, H>, Vec<(Pos<i64>, Option<S>)>>;
+pub type SavedGridHistory<S, H = FxBuildHasher> =
+    SavedHistory<Grid<S, H>, Vec<(Pos<i64>, Option<S>)>>;
 
 /// A (Game of) Life-like automaton.
 ///
@@ -45,71 +66,346 @@ pub type StepFn<S, D> = fn(Pos, Option<S>, D) -> Option<S>;
 /// alive** and the ones around them in a **fixed radius**.
 ///
 /// The name might feel a bit misleading because the automaton
-/// actually allows for much more advanced features than GoL has
+/// actually allows for much more advanced features than `GoL` has
 /// (e.g. position-based logic, multiple states instead of only two,
 /// etc.).
-pub struct Automaton<S, D = ()> {
-    cells: Grid<S>,
+///
+/// The grid uses the hasher `H`, see
+/// [`AutomatonBuilder::init_with_hasher()`].
+pub struct Automaton<S, D = (), H = FxBuildHasher> {
+    cells: Grid<S, H>,
     radius: u8,
+    /// Number of generations computed so far.
+    generation: u64,
+    /// Generation at which the generation limit was reached, so that going
+    /// back in history doesn't extend the limit.
+    limit_reached_at: Option<u64>,
     generations_left: Option<u32>,
     step_fn: StepFn<S, D>,
-    data_fn: DataFn<S, D>,
+    data_fn: Collector<S, D, H>,
+    history: Option<GridHistory<S, H>>,
+    cycle_detector: Option<CycleDetector<Grid<S, H>>>,
+    stats: Option<StatsTracker<S, H>>,
+    observers: Option<Observers<Grid<S, H>, S, i64>>,
+}
+
+/// A data collection function, see [`InitBuilder::map()`] and
+/// [`InitBuilder::map_timed()`].
+enum Collector<S, D, H> {
+    Plain(DataFn<S, D, H>),
+    Timed(TimedDataFn<S, D, H>),
+}
+
+impl<S, D, H> Clone for Collector<S, D, H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S, D, H> Copy for Collector<S, D, H> {}
+
+impl<S, D, H> Collector<S, D, H> {
+    fn collect(&self, pos: Pos<i64>, state: Option<&S>, grid: &Grid<S, H>, generation: u64) -> D {
+        match self {
+            Self::Plain(data_fn) => data_fn(pos, state, grid),
+            Self::Timed(data_fn) => data_fn(pos, state, grid, generation),
+        }
+    }
+}
+
+/// Keeps [`Stats`] up to date.
+///
+/// See [`InitBuilder::track_stats()`].
+struct StatsTracker<S, H> {
+    stats: Stats<S, i64>,
+    /// `stats` no longer match the grid (e.g. after an edit) and have to be
+    /// recounted.
+    is_stale: bool,
+    recount_fn: fn(&mut Stats<S, i64>, &Grid<S, H>),
+    add_fn: fn(&mut Stats<S, i64>, &S),
+    remove_fn: fn(&mut Stats<S, i64>, &S),
+}
+
+impl<S: Clone + Hash + Eq, H> StatsTracker<S, H> {
+    fn new(grid: &Grid<S, H>) -> Self {
+        let mut stats = Stats::default();
+        Self::recount_cells(&mut stats, grid);
+
+        Self {
+            stats,
+            is_stale: false,
+            recount_fn: Self::recount_cells,
+            add_fn: Stats::add_state,
+            remove_fn: Stats::remove_state,
+        }
+    }
+
+    fn recount_cells(stats: &mut Stats<S, i64>, grid: &Grid<S, H>) {
+        stats.recount(grid.iter().map(|(pos, state)| (*pos, state)));
+    }
+}
+
+impl<S, H> StatsTracker<S, H> {
+    /// Recounts the stats from the whole grid, keeping births and deaths.
+    fn recount(&mut self, grid: &Grid<S, H>) {
+        (self.recount_fn)(&mut self.stats, grid);
+        self.is_stale = false;
+    }
+
+    /// Records a live cell about to be evaluated during a step, before the
+    /// step function consumes its state.
+    fn record_evaluated(&mut self, state: &S) {
+        (self.remove_fn)(&mut self.stats, state);
+    }
+
+    /// Records the new state of a cell evaluated during a step, keeping all
+    /// stats up to date.
+    fn record_change(&mut self, pos: Pos<i64>, was_alive: bool, new: Option<&S>) {
+        if let Some(state) = new {
+            (self.add_fn)(&mut self.stats, state);
+        }
+
+        match (was_alive, new.is_some()) {
+            (false, true) => {
+                self.stats.births = self.stats.births.saturating_add(1);
+                self.stats.population = self.stats.population.saturating_add(1);
+                self.stats.include(pos);
+            }
+            (true, false) => {
+                self.stats.deaths = self.stats.deaths.saturating_add(1);
+                self.stats.population = self.stats.population.saturating_sub(1);
+                // The bounding box might shrink
+                self.is_stale |= self
+                    .stats
+                    .bounding_box
+                    .is_none_or(|rect| rect.is_on_edge(pos));
+            }
+            _ => (),
+        }
+    }
+}
+
+/// The state of an [`Automaton`] without its functions.
+///
+/// Returned by [`Automaton::snapshot()`].
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "S: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, H: BuildHasher + Default"
+    ))
+)]
+pub struct Checkpoint<S, H = FxBuildHasher> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::engine::serde_sparse"))]
+    pub cells: Grid<S, H>,
+    pub radius: u8,
+    /// See [`Automaton::generation()`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub generation: u64,
+    pub generations_left: Option<u32>,
+    /// Recorded history, see [`Automaton::history()`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::engine::serde_sparse::history")
+    )]
+    pub history: Option<SavedGridHistory<S, H>>,
+    /// Longest period that cycle detection looks for, see
+    /// [`Automaton::max_period()`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_period: Option<u32>,
+    /// Whether statistics are enabled, see [`Automaton::stats()`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub track_stats: bool,
+}
+
+// NOTE: Implemented manually, since hashers usually don't implement `Debug`
+// and `PartialEq`.
+impl<S: fmt::Debug, H> fmt::Debug for Checkpoint<S, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Checkpoint")
+            .field("cells", &self.cells)
+            .field("radius", &self.radius)
+            .field("generation", &self.generation)
+            .field("generations_left", &self.generations_left)
+            .field("history", &self.history)
+            .field("max_period", &self.max_period)
+            .field("track_stats", &self.track_stats)
+            .finish()
+    }
 }
 
-impl<S, D> Automaton<S, D> {
+impl<S: PartialEq, H: BuildHasher> PartialEq for Checkpoint<S, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+            && self.radius == other.radius
+            && self.generation == other.generation
+            && self.generations_left == other.generations_left
+            && self.history == other.history
+            && self.max_period == other.max_period
+            && self.track_stats == other.track_stats
+    }
+}
+
+impl<S: Eq, H: BuildHasher> Eq for Checkpoint<S, H> {}
+
+impl<S, D, H: BuildHasher + Default> Automaton<S, D, H> {
     pub fn new(
-        cells: Grid<S>,
+        cells: Grid<S, H>,
+        radius: u8,
+        generations_left: Option<u32>,
+        step_fn: StepFn<S, D>,
+        data_fn: DataFn<S, D, H>,
+    ) -> Self {
+        Self::with_collector(
+            cells,
+            radius,
+            generations_left,
+            step_fn,
+            Collector::Plain(data_fn),
+        )
+    }
+
+    /// Like [`new()`](Self::new), but with a data function that takes the
+    /// current generation (see [`InitBuilder::map_timed()`]).
+    pub fn new_timed(
+        cells: Grid<S, H>,
+        radius: u8,
+        generations_left: Option<u32>,
+        step_fn: StepFn<S, D>,
+        data_fn: TimedDataFn<S, D, H>,
+    ) -> Self {
+        Self::with_collector(
+            cells,
+            radius,
+            generations_left,
+            step_fn,
+            Collector::Timed(data_fn),
+        )
+    }
+
+    fn with_collector(
+        cells: Grid<S, H>,
         radius: u8,
         generations_left: Option<u32>,
         step_fn: StepFn<S, D>,
-        data_fn: DataFn<S, D>,
+        data_fn: Collector<S, D, H>,
     ) -> Self {
         Self {
             cells,
             radius,
+            generation: 0,
+            limit_reached_at: None,
             generations_left,
             step_fn,
             data_fn,
+            history: None,
+            cycle_detector: None,
+            stats: None,
+            observers: None,
         }
     }
 
     /// Computes the next generation.
+    ///
+    /// If cycle detection is enabled (see [`InitBuilder::detect_cycles()`]),
+    /// [`ExecutionState::Extinct`] and [`ExecutionState::Stabilized`] take
+    /// precedence over [`ExecutionState::Remaining`] and
+    /// [`ExecutionState::Infinite`].
     pub fn step(&mut self) -> ExecutionState {
-        let radius = self.radius as isize;
-        // NOTE: The number of synthetic cells with always be
-        // `self.cells.len() * f(RADIUS)` (where f(x) - see below)
-        // TODO: Generalize (`9`)
-        let mut synthetic_cells = Vec::with_capacity(9 * self.cells.len());
+        if self.is_finished() {
+            self.limit_reached_at.get_or_insert(self.generation);
+        }
 
-        // Create synthetic cells
-        self.cells.keys().for_each(|(x, y)| {
-            // OPTIM: Use a macro to expand to all `(dx, dy)`'s
-            (-radius..=radius)
-                .cartesian_product(-radius..=radius)
-                .for_each(|(dx, dy)| {
-                    x.checked_add_signed(dx).and_then(|x| {
-                        y.checked_add_signed(dy).map(|y| {
-                            if !self.cells.contains_key(&(x, y)) {
-                                synthetic_cells.push((x, y))
-                            }
-                        })
-                    });
-                });
+        let mut observers = self.observers.take();
+
+        if let Some(observers) = &mut observers {
+            observers.before_step(&self.cells);
+        }
+
+        if let Some(history) = &mut self.history {
+            history.sync(&self.cells);
+        }
+
+        if let Some(detector) = &mut self.cycle_detector {
+            detector.seed(&self.cells);
+        }
+
+        let mut stats_tracker = self.stats.take();
+        self.step_cells(stats_tracker.as_mut(), observers.as_mut());
+        self.stats = stats_tracker;
+
+        self.generation = self.generation.saturating_add(1);
+
+        if let Some(stats_tracker) = self.stats.as_mut().filter(|stats| stats.is_stale) {
+            stats_tracker.recount(&self.cells);
+        }
+
+        if let Some(history) = &mut self.history {
+            history.record(&self.cells);
+        }
+
+        let cycle_state = self.cycle_detector.as_mut().and_then(|detector| {
+            let period = detector.record(&self.cells);
+
+            if self.cells.is_empty() {
+                Some(ExecutionState::Extinct)
+            } else {
+                period.map(|period| ExecutionState::Stabilized { period })
+            }
+        });
+
+        // Return proper execution state signal
+        let state = self.generations_left.map_or(ExecutionState::Infinite, |x| {
+            x.checked_sub(1).map_or(ExecutionState::Finished, |y| {
+                self.generations_left = Some(y);
+                ExecutionState::Remaining(y)
+            })
         });
 
-        // OPTIM: Get rid of these calls
-        // IDEA: Use a binary tree instead of `Vec`
-        synthetic_cells.sort();
-        synthetic_cells.dedup();
+        let state = match (state, cycle_state) {
+            (ExecutionState::Finished, _) => ExecutionState::Finished,
+            (_, Some(cycle_state)) => cycle_state,
+            (state, None) => state,
+        };
+
+        if let Some(observers) = &mut observers {
+            observers.after_step(&self.cells, state);
+        }
+
+        self.observers = observers;
+
+        state
+    }
+
+    /// Evaluates the live cells and the dead ones around them.
+    ///
+    /// # Panics
+    /// Never panics, the data for every cell is always collected before the
+    /// step function is evaluated.
+    fn step_cells(
+        &mut self,
+        mut stats_tracker: Option<&mut StatsTracker<S, H>>,
+        mut observers: Option<&mut Observers<Grid<S, H>, S, i64>>,
+    ) {
+        // Create synthetic cells
+        let synthetic_cells = surrounding_cells(&self.cells, self.radius);
 
         // Collect data for each cell (both real and synthetic)
         // OPTIM: Don't collect, evaluate `data_fn` in-place.
-        let mut cells_data: HashMap<Pos, D> = self
+        let mut cells_data: HashMap<Pos<i64>, D, H> = self
             .cells
             .iter()
             .map(|(pos, state)| (*pos, Some(state)))
             .chain(synthetic_cells.iter().map(|pos| (*pos, None)))
-            .map(|(pos, state)| (pos, (self.data_fn)(pos, state, &self.cells)))
+            .map(|(pos, state)| {
+                (
+                    pos,
+                    self.data_fn
+                        .collect(pos, state, &self.cells, self.generation),
+                )
+            })
             .collect();
 
         // Evaluate `step_fn()` for each cell and collect new states
@@ -117,150 +413,1299 @@ impl<S, D> Automaton<S, D> {
         // NOTE: We have iterated over all elements of `self.cells`
         // and wrote corresponding `cells_data`, so it must always
         // exist.
-        let cells: HashMap<_, _> = self.cells.drain().collect();
+        let cells: Grid<S, H> = self.cells.drain().collect();
+
+        if let Some(stats_tracker) = stats_tracker.as_deref_mut() {
+            stats_tracker.stats.births = 0;
+            stats_tracker.stats.deaths = 0;
+        }
+
+        for (pos, state) in cells {
+            let old_state = observers
+                .as_ref()
+                .and_then(|observers| observers.save_state(&state));
+
+            if let Some(stats_tracker) = stats_tracker.as_deref_mut() {
+                stats_tracker.record_evaluated(&state);
+            }
 
-        cells.into_iter().for_each(|(pos, state)| {
             let new_state = (self.step_fn)(pos, Some(state), cells_data.remove(&pos).unwrap());
 
-            match new_state {
-                None => (),
-                Some(s) => {
-                    self.cells.insert(pos, s);
-                }
-            };
-        });
+            if let (Some(observers), Some(old_state)) = (observers.as_deref_mut(), &old_state) {
+                observers.cell_evaluated(pos, Some(old_state), new_state.as_ref());
+            }
+
+            if let Some(stats_tracker) = stats_tracker.as_deref_mut() {
+                stats_tracker.record_change(pos, true, new_state.as_ref());
+            }
+
+            if let Some(s) = new_state {
+                self.cells.insert(pos, s);
+            }
+        }
 
-        synthetic_cells.iter().for_each(|pos| {
+        for pos in &synthetic_cells {
             // NOTE: See above for **safety**
             if let Some(s) = (self.step_fn)(*pos, None, cells_data.remove(pos).unwrap()) {
+                if let Some(observers) = observers.as_deref_mut() {
+                    observers.cell_evaluated(*pos, None, Some(&s));
+                }
+
+                if let Some(stats_tracker) = stats_tracker.as_deref_mut() {
+                    stats_tracker.record_change(*pos, false, Some(&s));
+                }
+
                 self.cells.insert(*pos, s);
             }
+        }
+    }
+
+    pub const fn cells(&self) -> &Grid<S, H> {
+        &self.cells
+    }
+
+    /// Returns a mutable view of the cell grid.
+    pub const fn cells_mut(&mut self) -> CellsMut<'_, S, D, H> {
+        CellsMut { automaton: self }
+    }
+
+    /// Returns the state of the cell at `pos`, or `None` if it's dead.
+    pub fn get(&self, pos: Pos<i64>) -> Option<&S> {
+        self.cells.get(&pos)
+    }
+
+    /// Sets the state of the cell at `pos` (`None` kills it), returning the
+    /// previous one.
+    pub fn set(&mut self, pos: Pos<i64>, state: Option<S>) -> Option<S> {
+        self.mark_edited();
+
+        match state {
+            Some(s) => self.cells.insert(pos, s),
+            None => self.cells.remove(&pos),
+        }
+    }
+
+    /// Kills all cells.
+    pub fn clear(&mut self) {
+        self.mark_edited();
+        self.cells.clear();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.generations_left == Some(0)
+    }
+
+    /// Returns the number of generations computed so far.
+    ///
+    /// Going back in history (see [`Automaton::step_back()`] and
+    /// [`Automaton::seek()`]) restores the number of the restored
+    /// generation.
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns a [`Generations`] view, which computes one generation at a
+    /// time until the generation limit is reached.
+    pub const fn generations(&mut self) -> Generations<'_, S, D, H> {
+        Generations { automaton: self }
+    }
+
+    /// Computes up to `generations` generations, stopping early if the
+    /// generation limit is reached.
+    ///
+    /// Returns the state returned by the last step.
+    pub fn run_for(&mut self, generations: u32) -> ExecutionState {
+        let mut state = ExecutionState::from_generations_left(self.generations_left);
+
+        for _ in 0..generations {
+            if self.is_finished() {
+                break;
+            }
+
+            state = self.step();
+        }
+
+        state
+    }
+
+    /// Computes generations until `predicate` returns `true` for the
+    /// automaton (checked after each step), or the generation limit is
+    /// reached.
+    ///
+    /// Returns the state returned by the last step.
+    pub fn run_until<F: FnMut(&Self) -> bool>(&mut self, mut predicate: F) -> ExecutionState {
+        let mut state = ExecutionState::from_generations_left(self.generations_left);
+
+        while !self.is_finished() {
+            state = self.step();
+
+            if predicate(self) {
+                break;
+            }
+        }
+
+        state
+    }
+
+    /// Returns the recorded history, if enabled.
+    ///
+    /// See [`InitBuilder::history()`].
+    pub const fn history(&self) -> Option<&GridHistory<S, H>> {
+        self.history.as_ref()
+    }
+
+    /// Returns a mutable reference to the recorded history, if enabled.
+    pub const fn history_mut(&mut self) -> Option<&mut GridHistory<S, H>> {
+        self.history.as_mut()
+    }
+
+    /// Restores the previous recorded generation.
+    ///
+    /// Returns `false` if history is disabled or there is nothing to go back
+    /// to.
+    pub fn step_back(&mut self) -> bool {
+        self.history
+            .as_ref()
+            .and_then(History::previous_generation)
+            .is_some_and(|generation| self.rewind(generation))
+    }
+
+    /// Moves to the given generation (see [`generation()`](Self::generation)),
+    /// restoring it from history or computing it.
+    ///
+    /// Returns `false` if history is disabled or the generation was thinned
+    /// out.
+    pub fn seek(&mut self, generation: u64) -> bool {
+        let Some(latest) = self.history.as_ref().map(History::latest_generation) else {
+            return false;
+        };
+
+        if generation <= latest {
+            return self.rewind(generation);
+        }
+
+        for _ in latest..generation {
+            self.step();
+        }
+
+        true
+    }
+
+    /// Restores a recorded generation, discarding all newer ones.
+    fn rewind(&mut self, generation: u64) -> bool {
+        let Some(history) = &mut self.history else {
+            return false;
+        };
+        let Some(cells) = history.rewind(generation) else {
+            return false;
+        };
+        // The generation at which the limit is (or was) reached
+        let limit_end = self.limit_reached_at.or_else(|| {
+            self.generations_left
+                .map(|x| self.generation.saturating_add(x.into()))
         });
 
-        // Return proper execution state signal
-        self.generations_left.map_or(ExecutionState::Infinite, |x| {
-            x.checked_sub(1).map_or(ExecutionState::Finished, |y| {
-                self.generations_left = Some(y);
-                ExecutionState::Remaining(y)
+        self.cells = cells;
+        self.generation = generation;
+        self.generations_left =
+            limit_end.map(|end| u32::try_from(end.saturating_sub(generation)).unwrap_or(u32::MAX));
+        self.limit_reached_at = self.limit_reached_at.filter(|end| *end <= generation);
+
+        if let Some(detector) = &mut self.cycle_detector {
+            detector.reset();
+        }
+
+        if let Some(stats_tracker) = &mut self.stats {
+            stats_tracker.recount(&self.cells);
+        }
+
+        true
+    }
+
+    /// Returns the statistics of the last computed generation, or `None` if
+    /// they're disabled.
+    ///
+    /// **Note:** Edits made since the last step are only taken into account
+    /// after the next one.
+    ///
+    /// See [`InitBuilder::track_stats()`].
+    pub fn stats(&self) -> Option<&Stats<S, i64>> {
+        self.stats
+            .as_ref()
+            .map(|stats_tracker| &stats_tracker.stats)
+    }
+
+    /// Returns the longest period that cycle detection looks for, or `None`
+    /// if it's disabled.
+    ///
+    /// See [`InitBuilder::detect_cycles()`].
+    pub fn max_period(&self) -> Option<u32> {
+        self.cycle_detector.as_ref().map(CycleDetector::max_period)
+    }
+
+    /// Notifies the history, cycle detection and statistics that the grid
+    /// was modified outside of a step.
+    fn mark_edited(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.mark_dirty();
+        }
+
+        if let Some(detector) = &mut self.cycle_detector {
+            detector.reset();
+        }
+
+        if let Some(stats_tracker) = &mut self.stats {
+            stats_tracker.is_stale = true;
+        }
+    }
+}
+
+impl<S: Clone, D, H: BuildHasher + Default + Clone> Automaton<S, D, H> {
+    /// Returns the state of the automaton, which can be used to
+    /// [`restore()`](Self::restore) it later.
+    ///
+    /// The recorded history and the cycle detection and statistics settings
+    /// are saved as well. Observers aren't saved.
+    pub fn snapshot(&self) -> Checkpoint<S, H> {
+        Checkpoint {
+            cells: self.cells.clone(),
+            radius: self.radius,
+            generation: self.generation,
+            generations_left: self.generations_left,
+            history: self.history.as_ref().map(History::save),
+            max_period: self.max_period(),
+            track_stats: self.stats.is_some(),
+        }
+    }
+
+    /// Sets all cells inside of `rect` to `state` (`None` kills them).
+    pub fn fill_rect(&mut self, rect: Rect<i64>, state: Option<S>) {
+        self.mark_edited();
+
+        let positions = rect.positions();
+
+        match state {
+            Some(s) => self.cells.extend(positions.map(|pos| (pos, s.clone()))),
+            None => {
+                for pos in positions {
+                    self.cells.remove(&pos);
+                }
+            }
+        }
+    }
+}
+
+/// A mutable view of an automaton's cell grid.
+///
+/// Returned by [`Automaton::cells_mut()`].
+pub struct CellsMut<'a, S, D, H = FxBuildHasher> {
+    automaton: &'a mut Automaton<S, D, H>,
+}
+
+impl<S, D, H> Deref for CellsMut<'_, S, D, H> {
+    type Target = Grid<S, H>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.automaton.cells
+    }
+}
+
+impl<S, D, H: BuildHasher + Default> DerefMut for CellsMut<'_, S, D, H> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.automaton.mark_edited();
+
+        &mut self.automaton.cells
+    }
+}
+
+/// Computes generations of an automaton one by one.
+///
+/// Returned by [`Automaton::generations()`].
+///
+/// # Examples
+/// ```rust
+/// # use lifers::engine::life_like::{AutomatonBuilder, Grid};
+/// # let mut game = AutomatonBuilder::new(1)
+/// #     .init(Grid::<bool>::default)
+/// #     .generations(3)
+/// #     .run(|_, state, ()| state);
+/// let mut generations = game.generations();
+///
+/// while let Some((state, cells)) = generations.next() {
+///     println!("{state:?}: {} cells", cells.len());
+/// }
+///
+/// assert!(game.is_finished());
+/// ```
+pub struct Generations<'a, S, D, H = FxBuildHasher> {
+    automaton: &'a mut Automaton<S, D, H>,
+}
+
+impl<S, D, H: BuildHasher + Default> Generations<'_, S, D, H> {
+    /// Computes the next generation, returning the state returned by
+    /// [`Automaton::step()`] along with the new grid.
+    ///
+    /// Returns `None` once the generation limit is reached.
+    #[allow(
+        clippy::should_implement_trait,
+        reason = "Grids borrow from `self`, which `Iterator` doesn't allow"
+    )]
+    pub fn next(&mut self) -> Option<(ExecutionState, &Grid<S, H>)> {
+        if self.automaton.is_finished() {
+            return None;
+        }
+
+        let state = self.automaton.step();
+
+        Some((state, &self.automaton.cells))
+    }
+}
+
+impl<S, D, H: BuildHasher> Automaton<S, D, H> {
+    /// Converts the part of the grid inside of `area` into a
+    /// [`generic::Automaton`] with the given rule, keeping the generation
+    /// counter and limit.
+    ///
+    /// See [`convert::to_dense()`] for `area` and `convert`.
+    pub fn to_generic<T, E, F>(
+        &self,
+        area: Rect<i64>,
+        convert: F,
+        step_fn: generic::StepFn<T, E>,
+        data_fn: generic::DataFn<T, E>,
+    ) -> generic::Automaton<T, E>
+    where
+        T: Clone + Hash + Eq,
+        F: Fn(Option<&S>) -> T,
+    {
+        let checkpoint = generic::Checkpoint {
+            cells: convert::to_dense(&self.cells, area, convert),
+            generation: self.generation,
+            generations_left: self.generations_left,
+            tracking: None,
+            auto_grow: None,
+            history: None,
+            cycles: None,
+            track_stats: None,
+        };
+
+        generic::Automaton::restore(checkpoint, step_fn, data_fn)
+    }
+}
+
+impl<S, D, H: BuildHasher + Default> engine::Automaton for Automaton<S, D, H> {
+    type State = S;
+    type Coord = i64;
+
+    fn step(&mut self) -> ExecutionState {
+        self.step()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.is_finished()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn generations_left(&self) -> Option<u32> {
+        self.generations_left
+    }
+
+    fn get(&self, pos: Pos<i64>) -> Option<&S> {
+        self.get(pos)
+    }
+
+    fn iter_cells(&self) -> impl Iterator<Item = (Pos<i64>, &S)> {
+        self.cells.iter().map(|(pos, state)| (*pos, state))
+    }
+
+    /// Returns the bounding box of the live cells.
+    fn bounding_box(&self) -> Option<Rect<i64>> {
+        Rect::from_positions(self.cells.keys().copied())
+    }
+}
+
+impl<S: Clone + PartialEq, D, H: BuildHasher + Default + Clone> Automaton<S, D, H> {
+    /// Computes generations until the grid stops changing (or dies out), or
+    /// the generation limit is reached.
+    ///
+    /// Returns [`ExecutionState::Stabilized`] with a period of 1 once a
+    /// generation is the same as the previous one, and
+    /// [`ExecutionState::Extinct`] once no cells are alive. If cycle
+    /// detection is enabled, also stops once a step returns
+    /// [`ExecutionState::Stabilized`]. Otherwise, returns the state returned
+    /// by the last step.
+    ///
+    /// **Note:** Without a generation limit or cycle detection, this never
+    /// returns for patterns that keep changing (e.g. oscillators).
+    pub fn run_while_changing(&mut self) -> ExecutionState {
+        let mut state = ExecutionState::from_generations_left(self.generations_left);
+
+        while !self.is_finished() {
+            let previous = self.cells.clone();
+
+            state = self.step();
+
+            if self.cells.is_empty() {
+                return ExecutionState::Extinct;
+            }
+
+            if self.cells == previous {
+                return ExecutionState::Stabilized { period: 1 };
+            }
+
+            if matches!(
+                state,
+                ExecutionState::Extinct | ExecutionState::Stabilized { .. }
+            ) {
+                break;
+            }
+        }
+
+        state
+    }
+
+    /// Starts recording history, see [`InitBuilder::history()`].
+    ///
+    /// The current grid is recorded as the current
+    /// [`generation()`](Self::generation).
+    pub fn record_history(&mut self, budget: usize) {
+        self.history = Some(History::new(&self.cells, self.generation, budget));
+    }
+
+    /// Subscribes an observer, see [`InitBuilder::observe()`].
+    pub fn observe<O: Observer<Grid<S, H>, S, i64> + 'static>(&mut self, observer: O) {
+        self.observers
+            .get_or_insert_with(Observers::new)
+            .push(Box::new(observer));
+    }
+
+    /// Unsubscribes all observers.
+    pub fn clear_observers(&mut self) {
+        self.observers = None;
+    }
+}
+
+impl<S: Hash, D, H> Automaton<S, D, H> {
+    /// Enables cycle detection, see [`InitBuilder::detect_cycles()`].
+    pub fn detect_cycles(&mut self, max_period: u32) {
+        self.cycle_detector = Some(CycleDetector::new(max_period, hash_shape));
+    }
+}
+
+impl<S: Clone + Hash + Eq, D, H: BuildHasher + Default + Clone> Automaton<S, D, H> {
+    /// Restores an automaton saved with [`snapshot()`](Self::snapshot),
+    /// using the given functions.
+    ///
+    /// The recorded history is restored as well, unless it's invalid (see
+    /// [`History::restore()`]), in which case it starts anew with the
+    /// restored grid. Cycle detection starts anew with the saved settings,
+    /// and statistics are recounted from the restored grid.
+    pub fn restore(
+        checkpoint: Checkpoint<S, H>,
+        step_fn: StepFn<S, D>,
+        data_fn: DataFn<S, D, H>,
+    ) -> Self {
+        Self::restore_with(checkpoint, step_fn, Collector::Plain(data_fn))
+    }
+
+    /// Like [`restore()`](Self::restore), but with a data function that
+    /// takes the current generation (see [`InitBuilder::map_timed()`]).
+    pub fn restore_timed(
+        checkpoint: Checkpoint<S, H>,
+        step_fn: StepFn<S, D>,
+        data_fn: TimedDataFn<S, D, H>,
+    ) -> Self {
+        Self::restore_with(checkpoint, step_fn, Collector::Timed(data_fn))
+    }
+
+    fn restore_with(
+        checkpoint: Checkpoint<S, H>,
+        step_fn: StepFn<S, D>,
+        data_fn: Collector<S, D, H>,
+    ) -> Self {
+        let mut automaton = Self::with_collector(
+            checkpoint.cells,
+            checkpoint.radius,
+            checkpoint.generations_left,
+            step_fn,
+            data_fn,
+        );
+        automaton.generation = checkpoint.generation;
+        automaton.history = checkpoint.history.map(|saved| {
+            let config = saved.config;
+
+            History::restore(saved).unwrap_or_else(|| {
+                History::with_config(&automaton.cells, automaton.generation, config)
             })
+        });
+        automaton.cycle_detector = checkpoint
+            .max_period
+            .map(|max_period| CycleDetector::new(max_period, hash_shape));
+
+        if checkpoint.track_stats {
+            automaton.track_stats();
+        }
+
+        automaton
+    }
+}
+
+impl<S: Clone + Hash + Eq, D, H: BuildHasher + Default> Automaton<S, D, H> {
+    /// Enables statistics, see [`InitBuilder::track_stats()`].
+    ///
+    /// They're counted from the current grid right away.
+    pub fn track_stats(&mut self) {
+        self.stats = Some(StatsTracker::new(&self.cells));
+    }
+
+    /// Splits the grid into objects and classifies them using this
+    /// automaton's rule, see [`Census`].
+    pub fn census(&self, options: SplitOptions, max_period: u32) -> Census<S> {
+        let mut generation = self.generation;
+
+        Census::take(&self.analyzed_cells(), options, max_period, |cells| {
+            let cells = self.step_isolated(cells, generation);
+            generation = generation.saturating_add(1);
+
+            cells
         })
     }
 
-    pub fn cells(&self) -> &Grid<S> {
-        &self.cells
+    /// Computes the [apgcode] of the whole grid as a single
+    /// object, using this automaton's rule.
+    pub fn apgcode(&self, max_period: u32) -> Option<String> {
+        let mut generation = self.generation;
+
+        apgcode::encode(&self.analyzed_cells(), max_period, |cells| {
+            let cells = self.step_isolated(cells, generation);
+            generation = generation.saturating_add(1);
+
+            cells
+        })
     }
 
-    pub fn is_finished(&self) -> bool {
-        self.generations_left.map_or(false, |x| x == 0)
+    /// Returns a copy of the grid with the hasher used by the
+    /// [`analysis`](crate::analysis) module.
+    fn analyzed_cells(&self) -> Grid<S> {
+        self.cells
+            .iter()
+            .map(|(pos, state)| (*pos, state.clone()))
+            .collect()
+    }
+
+    /// Computes the next generation of `cells` (as the given generation)
+    /// with this automaton's rule, without affecting it.
+    fn step_isolated(&self, cells: Grid<S>, generation: u64) -> Grid<S> {
+        let mut automaton = Self::with_collector(
+            cells.into_iter().collect(),
+            self.radius,
+            None,
+            self.step_fn,
+            self.data_fn,
+        );
+        automaton.generation = generation;
+        automaton.step();
+
+        automaton.cells.into_iter().collect()
     }
 }
 
+/// Implements optional engine features for builders.
+macro_rules! impl_builder_options {
+    () => {
+        /// Enables history recording, which allows to go back to previous
+        /// generations with [`Automaton::step_back()`] and
+        /// [`Automaton::seek()`].
+        ///
+        /// Once the history takes up more than `budget` bytes, older
+        /// generations are thinned out (see [`History`]). The initial grid is
+        /// recorded as generation 0.
+        pub fn history(mut self, budget: usize) -> Self
+        where
+            S: Clone + PartialEq,
+            H: Clone,
+        {
+            self.history = Some(History::new(&self.cells, 0, budget));
+
+            self
+        }
+
+        /// Enables cycle detection.
+        ///
+        /// After each step, the grid is compared (by its hash) to the last
+        /// `max_period` generations. [`Automaton::step()`] then returns
+        /// [`ExecutionState::Stabilized`] when the grid repeats one of them,
+        /// or [`ExecutionState::Extinct`] when no cells are alive.
+        ///
+        /// Grids are compared regardless of their position, so moving
+        /// patterns (e.g. spaceships) are detected as well.
+        ///
+        /// Edits made between steps restart the detection.
+        pub fn detect_cycles(mut self, max_period: u32) -> Self
+        where
+            S: Hash,
+        {
+            self.cycle_detector = Some(CycleDetector::new(max_period, hash_shape));
+
+            self
+        }
+
+        /// Enables statistics (see [`Stats`]), available through
+        /// [`Automaton::stats()`].
+        ///
+        /// All stats are updated while the step is computed. The live cells
+        /// are only recounted when the bounding box might have shrunk or
+        /// the grid was edited.
+        pub fn track_stats(mut self) -> Self
+        where
+            S: Clone + Hash + Eq,
+        {
+            self.stats = Some(StatsTracker::new(&self.cells));
+
+            self
+        }
+
+        /// Subscribes an observer, which is then notified about every step
+        /// (see [`Observer`]).
+        ///
+        /// Observers are notified in the order they were added. To access an
+        /// observer afterwards, pass it wrapped in an `Rc<RefCell<_>>`.
+        pub fn observe<O: Observer<Grid<S, H>, S, i64> + 'static>(mut self, observer: O) -> Self
+        where
+            S: Clone + PartialEq,
+        {
+            self.observers
+                .get_or_insert_with(Observers::new)
+                .push(Box::new(observer));
+
+            self
+        }
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AutomatonBuilder {
     radius: u8,
 }
 
 impl AutomatonBuilder {
-    pub fn new(radius: u8) -> Self {
+    pub const fn new(radius: u8) -> Self {
         Self { radius }
     }
 
     // NOTE: Should there be a `self` parameter?
-    pub fn init<F, S>(self, mut init_fn: F) -> InitBuilder<S>
+    pub fn init<F, S>(self, init_fn: F) -> InitBuilder<S>
     where
         F: FnMut() -> Grid<S>,
+    {
+        self.init_with_hasher(init_fn)
+    }
+
+    /// Like [`init()`](Self::init), but for grids with any hasher (e.g.
+    /// [`PosBuildHasher`]), which the automaton then uses as well.
+    ///
+    /// [`PosBuildHasher`]: crate::engine::hasher::PosBuildHasher
+    pub fn init_with_hasher<F, S, H>(self, mut init_fn: F) -> InitBuilder<S, H>
+    where
+        F: FnMut() -> Grid<S, H>,
     {
         InitBuilder {
             radius: self.radius,
             cells: init_fn(),
             generations_limit: None,
+            history: None,
+            cycle_detector: None,
+            stats: None,
+            observers: None,
         }
     }
 }
 
-pub struct InitBuilder<S> {
+pub struct InitBuilder<S, H = FxBuildHasher> {
     radius: u8,
-    cells: Grid<S>,
+    cells: Grid<S, H>,
     generations_limit: Option<u32>,
+    history: Option<GridHistory<S, H>>,
+    cycle_detector: Option<CycleDetector<Grid<S, H>>>,
+    stats: Option<StatsTracker<S, H>>,
+    observers: Option<Observers<Grid<S, H>, S, i64>>,
 }
 
-impl<S> InitBuilder<S> {
-    pub fn run(self, step_fn: StepFn<S, ()>) -> Automaton<S, ()> {
+impl<S, H: BuildHasher + Default> InitBuilder<S, H> {
+    pub fn run(self, step_fn: StepFn<S, ()>) -> Automaton<S, (), H> {
         Automaton {
             radius: self.radius,
             cells: self.cells,
+            generation: 0,
+            limit_reached_at: None,
             generations_left: self.generations_limit,
-            data_fn: |_, _, _| (),
+            data_fn: Collector::Plain(|_, _, _| ()),
             step_fn,
+            history: self.history,
+            cycle_detector: self.cycle_detector,
+            stats: self.stats,
+            observers: self.observers,
         }
     }
 
-    pub fn map<D>(self, data_fn: DataFn<S, D>) -> MappedBuilder<S, D> {
+    pub fn map<D>(self, data_fn: DataFn<S, D, H>) -> MappedBuilder<S, D, H> {
+        self.map_with(Collector::Plain(data_fn))
+    }
+
+    /// Like [`map()`](Self::map), but the data collection function also
+    /// takes the number of the generation the data is collected from (see
+    /// [`Automaton::generation()`]).
+    ///
+    /// The generation can be passed on to the step function as a part of
+    /// the data, which allows for time-dependent rules.
+    pub fn map_timed<D>(self, data_fn: TimedDataFn<S, D, H>) -> MappedBuilder<S, D, H> {
+        self.map_with(Collector::Timed(data_fn))
+    }
+
+    fn map_with<D>(self, data_fn: Collector<S, D, H>) -> MappedBuilder<S, D, H> {
         MappedBuilder {
             radius: self.radius,
             cells: self.cells,
             generations_limit: self.generations_limit,
             data_fn,
+            history: self.history,
+            cycle_detector: self.cycle_detector,
+            stats: self.stats,
+            observers: self.observers,
         }
     }
 
     impl_builder_misc! { Self }
+    impl_builder_options! {}
 }
 
-pub struct MappedBuilder<S, D> {
+pub struct MappedBuilder<S, D, H = FxBuildHasher> {
     radius: u8,
-    cells: Grid<S>,
-    data_fn: DataFn<S, D>,
+    cells: Grid<S, H>,
+    data_fn: Collector<S, D, H>,
     generations_limit: Option<u32>,
+    history: Option<GridHistory<S, H>>,
+    cycle_detector: Option<CycleDetector<Grid<S, H>>>,
+    stats: Option<StatsTracker<S, H>>,
+    observers: Option<Observers<Grid<S, H>, S, i64>>,
 }
 
-impl<S, D> MappedBuilder<S, D> {
-    pub fn run(self, step_fn: StepFn<S, D>) -> Automaton<S, D> {
+impl<S, D, H: BuildHasher + Default> MappedBuilder<S, D, H> {
+    pub fn run(self, step_fn: StepFn<S, D>) -> Automaton<S, D, H> {
         Automaton {
             radius: self.radius,
             cells: self.cells,
+            generation: 0,
+            limit_reached_at: None,
             generations_left: self.generations_limit,
             data_fn: self.data_fn,
             step_fn,
+            history: self.history,
+            cycle_detector: self.cycle_detector,
+            stats: self.stats,
+            observers: self.observers,
         }
     }
 
     impl_builder_misc! { Self }
+    impl_builder_options! {}
 }
 
-/// Counts all neighbors using a hashmap containing only real cells.
-pub fn count_neighbors<S>(pos: Pos, radius: u8, grid: &Grid<S>) -> u32 {
-    let radius = radius as isize;
+/// Hashes the grid independently of its position (i.e. relative to the
+/// top-left corner of its bounding box) and iteration order.
+fn hash_shape<S: Hash, H>(grid: &Grid<S, H>) -> u64 {
+    let (min_x, min_y) = grid
+        .keys()
+        .copied()
+        .reduce(|(min_x, min_y), (x, y)| (min_x.min(x), min_y.min(y)))
+        .unwrap_or_default();
 
-    (-radius..=radius)
-        .cartesian_product(-radius..=radius)
-        .filter_map(|(dx, dy)| {
-            // OPTIM: Remove the `.flatten()`
-            (!(dx == 0 && dy == 0))
-                .then(|| {
-                    pos.0
-                        .checked_add_signed(dx)
-                        .and_then(|x| pos.1.checked_add_signed(dy).map(|y| (x, y)))
-                })
-                .flatten()
+    grid.iter()
+        .map(|((x, y), state)| {
+            let mut hasher = DefaultHasher::new();
+            (x.wrapping_sub(min_x), y.wrapping_sub(min_y), state).hash(&mut hasher);
+
+            hasher.finish()
         })
+        .fold(0, u64::wrapping_add)
+}
+
+/// Counts all neighbors using a hashmap containing only real cells.
+#[allow(
+    clippy::implicit_hasher,
+    reason = "False positive, `Grid` is already generic over its hasher"
+)]
+pub fn count_neighbors<S, H: BuildHasher>(pos: Pos<i64>, radius: u8, grid: &Grid<S, H>) -> u32 {
+    neighborhood(pos, radius)
         .map(|pos| grid.contains_key(&pos))
         .map(u32::from)
         .sum()
 }
 
+/// Returns the positions of the neighbors in `radius` (not including `pos`
+/// itself), leaving out the ones that would overflow.
+///
+/// Shared with the [`background`](super::background) engine.
+#[allow(
+    clippy::arithmetic_side_effects,
+    reason = "`radius` is an `u8`, so the range bounds can't overflow"
+)]
+pub(crate) fn neighborhood(pos: Pos<i64>, radius: u8) -> impl Iterator<Item = Pos<i64>> {
+    let radius = i64::from(radius);
+
+    // OPTIM: Use a macro to expand to all `(dx, dy)`'s
+    (-radius..=radius)
+        .cartesian_product(-radius..=radius)
+        .filter(|(dx, dy)| !(*dx == 0 && *dy == 0))
+        .filter_map(move |(dx, dy)| pos.0.checked_add(dx).zip(pos.1.checked_add(dy)))
+}
+
+/// Returns the positions of the cells that aren't stored, but are in
+/// `radius` of a stored one, i.e. all other cells that have to be evaluated
+/// in a step. They're sorted and unique.
+///
+/// Shared with the [`background`](super::background) engine.
+pub(crate) fn surrounding_cells<V, H: BuildHasher>(
+    cells: &HashMap<Pos<i64>, V, H>,
+    radius: u8,
+) -> Vec<Pos<i64>> {
+    // NOTE: The number of synthetic cells with always be
+    // `cells.len() * f(RADIUS)` (where f(x) - see below)
+    // TODO: Generalize (`9`)
+    let mut surrounding = Vec::with_capacity(cells.len().saturating_mul(9));
+
+    for pos in cells.keys() {
+        surrounding.extend(neighborhood(*pos, radius).filter(|pos| !cells.contains_key(pos)));
+    }
+
+    // OPTIM: Get rid of these calls
+    // IDEA: Use a binary tree instead of `Vec`
+    surrounding.sort_unstable();
+    surrounding.dedup();
+
+    surrounding
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{AutomatonBuilder, Grid};
+    use itertools::Itertools;
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{count_neighbors, AutomatonBuilder, Grid};
+    use crate::engine::{hasher::PosBuildHasher, observer::Observer, ExecutionState, Pos, Rect};
 
     #[test]
     fn builder() {
         let _ = AutomatonBuilder::new(1)
-            .init(|| Grid::default())
-            .run(|_, _: Option<()>, _| None);
+            .init(Grid::default)
+            .run(|_, _: Option<()>, ()| None);
 
         let _ = AutomatonBuilder::new(1)
-            .init(|| Grid::default())
+            .init(Grid::default)
             // .map(|_, _: Option<()>, _| ())
-            .run(|_, _: Option<()>, _| None);
+            .run(|_, _: Option<()>, ()| None);
+    }
+
+    #[test]
+    fn editing() {
+        let mut game = AutomatonBuilder::new(1)
+            .init(Grid::default)
+            .run(|_, state: Option<()>, ()| state);
+
+        assert_eq!(game.set((1, 2), Some(())), None);
+        assert_eq!(game.get((1, 2)), Some(&()));
+
+        game.fill_rect(Rect::new((0, 0), (3, 3)), Some(()));
+        assert_eq!(game.cells().len(), 9);
+
+        game.fill_rect(Rect::new((1, 1), (5, 5)), None);
+        assert_eq!(game.cells().len(), 5);
+
+        game.cells_mut().insert((10, 10), ());
+        assert_eq!(game.set((10, 10), None), Some(()));
+
+        game.clear();
+        assert!(game.cells().is_empty());
+    }
+
+    #[test]
+    fn history() {
+        let blinker: Grid<()> = [((1, 0), ()), ((1, 1), ()), ((1, 2), ())]
+            .into_iter()
+            .collect();
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| blinker.clone())
+            .map(|pos, _, cells| count_neighbors(pos, 1, cells))
+            .history(usize::MAX)
+            .run(|_, state, neighbors_n| match state {
+                Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+                None => (neighbors_n == 3).then_some(()),
+            });
+
+        game.step();
+        game.step();
+        assert_eq!(game.cells(), &blinker);
+        game.set((5, 5), Some(()));
+
+        assert!(game.step_back());
+        assert!(game.cells().contains_key(&(0, 1)));
+        assert!(game.seek(2));
+        assert_eq!(game.cells(), &blinker);
+        assert!(game.seek(0));
+        assert!(!game.step_back());
+
+        // Going back doesn't extend the generation limit past its end
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| blinker.clone())
+            .generations(2)
+            .history(usize::MAX)
+            .run(|_, state, ()| state);
+
+        for _ in 0..4_u8 {
+            game.step();
+        }
+
+        assert!(game.seek(1));
+        assert_eq!(game.step(), ExecutionState::Remaining(0));
+        assert_eq!(game.step(), ExecutionState::Finished);
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| [((0, 0), 1), ((1, 0), 2)].into_iter().collect())
+            .generations(5)
+            .history(1024)
+            .detect_cycles(4)
+            .track_stats()
+            .run(|_, state: Option<u8>, ()| state.map(|s| s.wrapping_add(1)));
+
+        game.step();
+
+        let checkpoint = game.snapshot();
+        let mut restored =
+            super::Automaton::restore(checkpoint.clone(), game.step_fn, |_, _, _| ());
+
+        assert_eq!(restored.snapshot(), checkpoint);
+        assert_eq!(game.step(), restored.step());
+        assert_eq!(game.cells(), restored.cells());
+
+        // The recorded history is restored as well
+        assert!(game.step_back() && restored.step_back());
+        assert_eq!(game.cells(), restored.cells());
+        assert!(restored.seek(0));
+        assert_eq!(restored.get((0, 0)), Some(&1));
+
+        // Cycle detection and statistics are restored as well
+        assert_eq!(restored.max_period(), Some(4));
+        assert_eq!(
+            restored.stats().unwrap().states,
+            restored.cells().values().copied().counts()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| [((0, 0), 1_u8), ((3, 1), 2)].into_iter().collect())
+            .history(1024)
+            .run(|_, state, ()| state.map(|s| s.wrapping_add(1)));
+        game.step();
+
+        let checkpoint = game.snapshot();
+        let json = serde_json::to_string(&checkpoint).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<super::Checkpoint<u8>>(&json).unwrap(),
+            checkpoint
+        );
+    }
+
+    #[test]
+    fn cycle_detection() {
+        let glider: Grid<()> = [
+            ((1, 0), ()),
+            ((2, 1), ()),
+            ((0, 2), ()),
+            ((1, 2), ()),
+            ((2, 2), ()),
+        ]
+        .into_iter()
+        .collect();
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| glider.clone())
+            .map(|pos, _, cells| count_neighbors(pos, 1, cells))
+            .detect_cycles(8)
+            .run(|_, state, neighbors_n| match state {
+                Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+                None => (neighbors_n == 3).then_some(()),
+            });
+
+        assert_eq!(game.max_period(), Some(8));
+
+        for _ in 0..3_u8 {
+            assert_eq!(game.step(), ExecutionState::Infinite);
+        }
+
+        // The glider moved by (1, 1)
+        assert_eq!(game.step(), ExecutionState::Stabilized { period: 4 });
+        assert!(game.cells().contains_key(&(3, 3)));
+
+        // Edits restart the detection
+        game.fill_rect(Rect::new((0, 0), (10, 10)), None);
+        game.set((20, 20), Some(()));
+
+        assert_eq!(game.step(), ExecutionState::Extinct);
+    }
+
+    #[test]
+    fn stats() {
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| {
+                [((0, 0), 1_u8), ((1, 0), 2), ((2, 0), 2)]
+                    .into_iter()
+                    .collect()
+            })
+            .map(|pos, _, cells| count_neighbors(pos, 1, cells))
+            .track_stats()
+            .run(|_, state, neighbors_n| {
+                state.map_or_else(
+                    || (neighbors_n == 3).then_some(3),
+                    |state| (neighbors_n == 2).then_some(state),
+                )
+            });
+
+        let stats = game.stats().unwrap();
+        assert_eq!(stats.population, 3);
+        assert_eq!(stats.bounding_box, Some(Rect::new((0, 0), (3, 1))));
+        assert_eq!(stats.states.get(&2), Some(&2));
+
+        game.step();
+
+        let stats = game.stats().unwrap();
+        assert_eq!((stats.births, stats.deaths), (2, 2));
+        assert_eq!(stats.population, 3);
+        assert_eq!(stats.bounding_box, Some(Rect::new((1, -1), (1, 3))));
+        assert_eq!(stats.states, [(2, 1), (3, 2)].into_iter().collect());
+
+        // Edited cells are taken into account
+        game.set((5, 5), Some(2));
+        game.step();
+
+        let stats = game.stats().unwrap();
+        assert_eq!((stats.births, stats.deaths), (2, 3));
+        assert_eq!(stats.population, 3);
+        assert_eq!(stats.bounding_box, Some(Rect::new((0, 0), (3, 1))));
+        assert_eq!(stats.states, [(2, 1), (3, 2)].into_iter().collect());
+
+        // Stats updated during steps match the live cells
+        game.fill_rect(Rect::new((0, -1), (3, 3)), None);
+
+        for pos in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            game.set(pos, Some(2));
+        }
+
+        for _ in 0..8_u8 {
+            game.step();
+
+            let stats = game.stats().unwrap();
+
+            assert_eq!(stats.population, game.cells().len());
+            assert_eq!(
+                stats.bounding_box,
+                Rect::from_positions(game.cells().keys().copied())
+            );
+            assert_eq!(stats.states, game.cells().values().copied().counts());
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        steps: usize,
+        changes: Vec<(Pos<i64>, Option<u8>, Option<u8>)>,
+    }
+
+    impl Observer<Grid<u8>, u8, i64> for Recorder {
+        fn before_step(&mut self, _cells: &Grid<u8>) {
+            self.steps = self.steps.saturating_add(1);
+        }
+
+        fn cell_changed(&mut self, pos: Pos<i64>, old: Option<&u8>, new: Option<&u8>) {
+            self.changes.push((pos, old.copied(), new.copied()));
+        }
+
+        fn observes_cells(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn observers() {
+        let recorder = Rc::new(RefCell::new(Recorder::default()));
+        // Every cell ages, cells with 3 neighbors are born
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| {
+                [((0, 0), 1_u8), ((1, 0), 1), ((2, 0), 2)]
+                    .into_iter()
+                    .collect()
+            })
+            .map(|pos, _, cells| count_neighbors(pos, 1, cells))
+            .observe(Rc::clone(&recorder))
+            .run(|_, state, neighbors_n| match state {
+                Some(1) => Some(1),
+                Some(_) => None,
+                None => (neighbors_n == 3).then_some(1),
+            });
+
+        game.step();
+
+        let mut changes = recorder.borrow().changes.clone();
+        changes.sort_unstable();
+
+        assert_eq!(recorder.borrow().steps, 1);
+        assert_eq!(
+            changes,
+            [
+                ((1, -1), None, Some(1)),
+                ((1, 1), None, Some(1)),
+                ((2, 0), Some(2), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn running() {
+        let conway = |cells: &[Pos<i64>], generations_limit| {
+            AutomatonBuilder::new(1)
+                .init(|| cells.iter().map(|pos| (*pos, ())).collect())
+                .map(|pos, _, cells| count_neighbors(pos, 1, cells))
+                .generations(generations_limit)
+                .run(|_, state, neighbors_n| match state {
+                    Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+                    None => (neighbors_n == 3).then_some(()),
+                })
+        };
+        let blinker = [(1, 0), (1, 1), (1, 2)];
+
+        let mut game = conway(&blinker, 5);
+        assert_eq!(game.run_for(3), ExecutionState::Remaining(2));
+        assert_eq!(game.run_for(10), ExecutionState::Remaining(0));
+        assert_eq!(game.run_for(1), ExecutionState::Finished);
+
+        let mut game = conway(&blinker, 3);
+        let mut generations = game.generations();
+        let mut states = Vec::new();
+
+        while let Some((state, cells)) = generations.next() {
+            assert_eq!(cells.len(), 3);
+            states.push(state);
+        }
+
+        assert_eq!(
+            states,
+            [
+                ExecutionState::Remaining(2),
+                ExecutionState::Remaining(1),
+                ExecutionState::Remaining(0)
+            ]
+        );
+
+        let mut game = conway(&blinker, 10);
+        assert_eq!(
+            game.run_until(|game| game.get((0, 1)).is_some()),
+            ExecutionState::Remaining(9)
+        );
+        assert_eq!(game.run_while_changing(), ExecutionState::Remaining(0));
+
+        let mut game = conway(&[(0, 0), (1, 0), (0, 1)], 10);
+        assert_eq!(
+            game.run_while_changing(),
+            ExecutionState::Stabilized { period: 1 }
+        );
+        assert_eq!(game.cells().len(), 4);
+
+        let mut game = conway(&[(0, 0), (1, 0)], 10);
+        assert_eq!(game.run_while_changing(), ExecutionState::Extinct);
+    }
+
+    #[test]
+    fn generation_counter() {
+        // Cells only live on to odd generations
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| std::iter::once(((0, 0), ())).collect())
+            .map_timed(|_, _, _, generation| generation)
+            .run(|_, state, generation| state.filter(|()| generation % 2 == 0));
+
+        game.step();
+        assert_eq!(game.generation(), 1);
+        assert_eq!(game.cells().len(), 1);
+
+        let mut restored =
+            super::Automaton::restore_timed(game.snapshot(), game.step_fn, |_, _, _, n| n);
+
+        game.step();
+        restored.step();
+        assert_eq!(game.generation(), 2);
+        assert!(game.cells().is_empty());
+        assert_eq!(restored.cells(), game.cells());
+    }
+
+    #[test]
+    fn hashers() {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let mut game = AutomatonBuilder::new(1)
+            .init_with_hasher(|| {
+                glider
+                    .iter()
+                    .map(|pos| (*pos, ()))
+                    .collect::<Grid<(), PosBuildHasher>>()
+            })
+            .map(|pos, _, cells| count_neighbors(pos, 1, cells))
+            .history(1024)
+            .detect_cycles(4)
+            .run(|_, state, neighbors_n| match state {
+                Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+                None => (neighbors_n == 3).then_some(()),
+            });
+
+        game.run_for(4);
+
+        assert_eq!(game.cells().len(), glider.len());
+        assert!(glider.iter().all(|(x, y)| game
+            .get((x.saturating_add(1), y.saturating_add(1)))
+            .is_some()));
+        assert!(game.step_back());
+        assert_eq!(game.generation(), 3);
+    }
+
+    #[test]
+    fn signed_coordinates() {
+        // Moving up and to the left, across both axes
+        let glider = [(0, 0), (1, 0), (2, 0), (0, 1), (1, 2)];
+        let mut game = AutomatonBuilder::new(1)
+            .init(|| glider.iter().map(|pos| (*pos, ())).collect())
+            .map(|pos, _, cells| count_neighbors(pos, 1, cells))
+            .run(|_, state, neighbors_n| match state {
+                Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+                None => (neighbors_n == 3).then_some(()),
+            });
+
+        game.run_for(40);
+
+        assert_eq!(
+            game.cells()
+                .keys()
+                .copied()
+                .collect::<std::collections::BTreeSet<_>>(),
+            glider
+                .iter()
+                .map(|(x, y)| (x.saturating_sub(10), y.saturating_sub(10)))
+                .collect()
+        );
     }
 }
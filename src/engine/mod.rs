@@ -1,14 +1,333 @@
 //! Automata engine components.
 
+pub mod background;
+pub mod convert;
+mod cycle;
 pub mod generic;
+pub mod hasher;
+pub mod history;
 pub mod life_like;
+pub mod observer;
+#[cfg(feature = "serde")]
+mod serde_sparse;
+pub mod totalistic;
 
-pub type Pos = (usize, usize);
+use std::{collections::HashMap, hash::Hash};
 
-#[derive(Debug, PartialEq, Eq)]
-#[allow(clippy::exhaustive_enums)]
+/// A position on a grid.
+///
+/// Dense grids ([`generic`]) use `usize` coordinates, while sparse ones
+/// ([`life_like`]) use `i64` coordinates, so that patterns can move in every
+/// direction.
+pub type Pos<T = usize> = (T, T);
+
+/// A coordinate of a [`Pos`]ition.
+pub trait Coord: Copy + Ord {
+    /// Returns the coordinate `distance` cells after `self`, saturating at
+    /// the bounds of the type.
+    fn offset(self, distance: usize) -> Self;
+
+    /// Returns the coordinate `distance` cells before `self`, saturating at
+    /// the bounds of the type.
+    fn offset_back(self, distance: usize) -> Self;
+
+    /// Returns the distance between two coordinates.
+    fn distance(self, other: Self) -> usize;
+}
+
+impl Coord for usize {
+    fn offset(self, distance: usize) -> Self {
+        self.saturating_add(distance)
+    }
+
+    fn offset_back(self, distance: usize) -> Self {
+        self.saturating_sub(distance)
+    }
+
+    fn distance(self, other: Self) -> usize {
+        self.abs_diff(other)
+    }
+}
+
+impl Coord for i64 {
+    fn offset(self, distance: usize) -> Self {
+        self.saturating_add_unsigned(u64::try_from(distance).unwrap_or(u64::MAX))
+    }
+
+    fn offset_back(self, distance: usize) -> Self {
+        self.saturating_sub_unsigned(u64::try_from(distance).unwrap_or(u64::MAX))
+    }
+
+    fn distance(self, other: Self) -> usize {
+        usize::try_from(self.abs_diff(other)).unwrap_or(usize::MAX)
+    }
+}
+
+/// An axis-aligned rectangle with its top-left corner at `(x, y)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect<T = usize> {
+    pub x: T,
+    pub y: T,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<T: Copy> Rect<T> {
+    pub const fn new((x, y): (T, T), (width, height): (usize, usize)) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+impl<T: Coord> Rect<T> {
+    /// Returns the smallest rectangle containing all of the positions, or
+    /// `None` if there are none.
+    pub fn from_positions<I: IntoIterator<Item = Pos<T>>>(positions: I) -> Option<Self> {
+        let ((min_x, min_y), (max_x, max_y)) = positions.into_iter().map(|pos| (pos, pos)).reduce(
+            |((min_x, min_y), (max_x, max_y)), ((x, y), _)| {
+                ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
+            },
+        )?;
+
+        Some(Self::new(
+            (min_x, min_y),
+            (
+                max_x.distance(min_x).saturating_add(1),
+                max_y.distance(min_y).saturating_add(1),
+            ),
+        ))
+    }
+
+    /// Returns `true` if the position lies on the rectangle's border.
+    pub fn is_on_edge(&self, (x, y): Pos<T>) -> bool {
+        self.contains((x, y))
+            && (x == self.x
+                || y == self.y
+                || x.distance(self.x).saturating_add(1) == self.width
+                || y.distance(self.y).saturating_add(1) == self.height)
+    }
+
+    /// Returns `true` if the position lies inside of the rectangle.
+    pub fn contains(&self, (x, y): Pos<T>) -> bool {
+        x >= self.x
+            && y >= self.y
+            && x.distance(self.x) < self.width
+            && y.distance(self.y) < self.height
+    }
+
+    /// Returns an iterator over all positions inside of the rectangle, row
+    /// by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos<T>> {
+        let Self {
+            x,
+            y,
+            width,
+            height,
+        } = *self;
+
+        (0..height).flat_map(move |dy| (0..width).map(move |dx| (x.offset(dx), y.offset(dy))))
+    }
+}
+
+/// Statistics of the last computed generation.
+///
+/// See [`generic::InitBuilder::track_stats()`] and
+/// [`life_like::InitBuilder::track_stats()`].
+#[derive(Debug, Clone)]
+pub struct Stats<S, T = usize> {
+    /// Number of live cells.
+    pub population: usize,
+    /// Number of cells that came to life in the last step.
+    pub births: usize,
+    /// Number of cells that died in the last step.
+    pub deaths: usize,
+    /// Smallest rectangle containing all live cells, `None` if there are
+    /// none.
+    pub bounding_box: Option<Rect<T>>,
+    /// Number of live cells in each state.
+    pub states: HashMap<S, usize>,
+}
+
+impl<S, T> Default for Stats<S, T> {
+    fn default() -> Self {
+        Self {
+            population: 0,
+            births: 0,
+            deaths: 0,
+            bounding_box: None,
+            states: HashMap::new(),
+        }
+    }
+}
+
+impl<S: Hash + Eq, T: PartialEq> PartialEq for Stats<S, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.population == other.population
+            && self.births == other.births
+            && self.deaths == other.deaths
+            && self.bounding_box == other.bounding_box
+            && self.states == other.states
+    }
+}
+
+impl<S: Hash + Eq, T: Eq> Eq for Stats<S, T> {}
+
+impl<S, T: Coord> Stats<S, T> {
+    /// Grows the bounding box to include `pos`.
+    fn include(&mut self, pos: Pos<T>) {
+        let corners = self.bounding_box.iter().flat_map(|rect| {
+            [
+                (rect.x, rect.y),
+                (
+                    rect.x.offset(rect.width.saturating_sub(1)),
+                    rect.y.offset(rect.height.saturating_sub(1)),
+                ),
+            ]
+        });
+
+        self.bounding_box = Rect::from_positions(corners.chain([pos]));
+    }
+}
+
+impl<S: Clone + Hash + Eq, T: Coord> Stats<S, T> {
+    /// Recounts the population, states and bounding box from live cells.
+    fn recount<'a, I>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = (Pos<T>, &'a S)>,
+        S: 'a,
+    {
+        let states = &mut self.states;
+        states.clear();
+
+        self.bounding_box = Rect::from_positions(cells.into_iter().map(|(pos, state)| {
+            let count = states.entry(state.clone()).or_default();
+            *count = count.saturating_add(1);
+
+            pos
+        }));
+        self.population = self.states.values().sum();
+    }
+
+    fn add_state(&mut self, state: &S) {
+        let count = self.states.entry(state.clone()).or_default();
+        *count = count.saturating_add(1);
+    }
+
+    fn remove_state(&mut self, state: &S) {
+        if let Some(count) = self.states.get_mut(state) {
+            *count = count.saturating_sub(1);
+
+            if *count == 0 {
+                self.states.remove(state);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(
+    clippy::exhaustive_enums,
+    reason = "Adding a state is a breaking change anyway"
+)]
 pub enum ExecutionState {
     Finished,
     Remaining(u32),
     Infinite,
+    /// The automaton repeats a previous generation `period` generations
+    /// back (a `period` of 1 means it's static).
+    ///
+    /// Only reported when cycle detection is enabled.
+    Stabilized {
+        period: u32,
+    },
+    /// All cells are dead.
+    ///
+    /// Only reported when cycle detection is enabled.
+    Extinct,
+}
+
+/// Functionality shared by the automata of all engines.
+///
+/// This allows to drive, render and analyze automata without knowing which
+/// engine they use. The engines' inherent methods of the same names behave
+/// the same way.
+///
+/// # Examples
+/// ```rust
+/// use lifers::engine::{generic, life_like, Automaton};
+///
+/// fn population<A: Automaton<State = bool>>(automaton: &A) -> usize {
+///     automaton.live_cells(|is_alive| *is_alive).count()
+/// }
+///
+/// let dense = generic::Automaton::build((4, 4))
+///     .init(|(x, y)| x == y)
+///     .run(|_, is_alive, ()| is_alive);
+/// let sparse = life_like::AutomatonBuilder::new(1)
+///     .init(|| [((0, 0), true), ((2, 1), true)].into_iter().collect())
+///     .run(|_, is_alive, ()| is_alive);
+///
+/// assert_eq!(population(&dense), 4);
+/// assert_eq!(population(&sparse), 2);
+/// ```
+pub trait Automaton {
+    /// The state of a cell.
+    type State;
+
+    /// The type of the cells' coordinates (see [`Pos`]).
+    type Coord: Coord;
+
+    /// Computes the next generation.
+    fn step(&mut self) -> ExecutionState;
+
+    /// Returns `true` if the generation limit has been reached.
+    fn is_finished(&self) -> bool;
+
+    /// Returns the number of generations computed so far.
+    fn generation(&self) -> u64;
+
+    /// Returns the number of generations left until the limit is reached, or
+    /// `None` if there is no limit.
+    fn generations_left(&self) -> Option<u32>;
+
+    /// Returns the state of the cell at `pos`, or `None` if it's dead (in
+    /// sparse engines) or out of bounds (in dense engines).
+    fn get(&self, pos: Pos<Self::Coord>) -> Option<&Self::State>;
+
+    /// Returns an iterator over all stored cells along with their positions.
+    ///
+    /// Dense engines store every cell of the grid, while sparse engines
+    /// only store live cells. The order is unspecified.
+    fn iter_cells(&self) -> impl Iterator<Item = (Pos<Self::Coord>, &Self::State)>;
+
+    /// Returns an iterator over the stored cells for which `is_alive` returns
+    /// `true`.
+    fn live_cells<F: Fn(&Self::State) -> bool>(
+        &self,
+        is_alive: F,
+    ) -> impl Iterator<Item = (Pos<Self::Coord>, &Self::State)> {
+        self.iter_cells().filter(move |(_, state)| is_alive(state))
+    }
+
+    /// Returns the smallest rectangle containing all stored cells, or `None`
+    /// if there are none.
+    fn bounding_box(&self) -> Option<Rect<Self::Coord>>;
+}
+
+impl ExecutionState {
+    /// Returns the state of an automaton with `generations_left` before any
+    /// generation is computed.
+    pub(crate) const fn from_generations_left(generations_left: Option<u32>) -> Self {
+        match generations_left {
+            None => Self::Infinite,
+            Some(0) => Self::Finished,
+            Some(n) => Self::Remaining(n),
+        }
+    }
 }
@@ -0,0 +1,167 @@
+//! Hooks for observing automata while they're stepped.
+//!
+//! Observers are notified before and after each generation, and optionally
+//! about every cell that changed in between. This allows plugins (stats
+//! collectors, recorders, frontends, etc.) to follow an automaton without
+//! its rule knowing about them.
+//!
+//! See [`generic::Automaton::observe()`](super::generic::Automaton::observe)
+//! and [`life_like::Automaton::observe()`](super::life_like::Automaton::observe).
+//!
+//! # Examples
+//! ```rust
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! use lifers::engine::{
+//!     life_like::{count_neighbors, AutomatonBuilder, Grid},
+//!     observer::Observer,
+//!     Pos,
+//! };
+//!
+//! #[derive(Default)]
+//! struct Births(usize);
+//!
+//! impl Observer<Grid<bool>, bool, i64> for Births {
+//!     fn cell_changed(&mut self, _pos: Pos<i64>, old: Option<&bool>, _new: Option<&bool>) {
+//!         if old.is_none() {
+//!             self.0 += 1;
+//!         }
+//!     }
+//!
+//!     fn observes_cells(&self) -> bool {
+//!         true
+//!     }
+//! }
+//!
+//! let births = Rc::new(RefCell::new(Births::default()));
+//! let mut game = AutomatonBuilder::new(1)
+//!     .init(|| [((1, 0), true), ((1, 1), true), ((1, 2), true)].into_iter().collect())
+//!     .map(|pos, _, cells| count_neighbors(pos, 1, cells))
+//!     .observe(Rc::clone(&births))
+//!     .run(|_, state, neighbors_n| match state {
+//!         Some(_) => (2..=3).contains(&neighbors_n).then_some(true),
+//!         None => (neighbors_n == 3).then_some(true),
+//!     });
+//!
+//! game.step();
+//! assert_eq!(births.borrow().0, 2);
+//! ```
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::engine::{ExecutionState, Pos};
+
+/// Receives notifications from an automaton with grids of type `G`, cells of
+/// type `S` and coordinates of type `T` (see [`Pos`]).
+///
+/// All methods do nothing by default.
+pub trait Observer<G, S, T = usize> {
+    /// Called before a generation is computed.
+    fn before_step(&mut self, _cells: &G) {}
+
+    /// Called after a generation is computed, with the state returned by
+    /// `step()`.
+    fn after_step(&mut self, _cells: &G, _state: ExecutionState) {}
+
+    /// Called for every cell that changed its state during a step, if
+    /// [`observes_cells()`](Self::observes_cells) returns `true`.
+    ///
+    /// Dead cells are `None`. In the [`generic`](super::generic) engine,
+    /// which has no dead cells, both states are always `Some`.
+    fn cell_changed(&mut self, _pos: Pos<T>, _old: Option<&S>, _new: Option<&S>) {}
+
+    /// Returns `true` if [`cell_changed()`](Self::cell_changed) should be
+    /// called. Checked before every step.
+    ///
+    /// Reporting changes requires the previous state of every evaluated cell
+    /// to be cloned, so it's disabled by default.
+    fn observes_cells(&self) -> bool {
+        false
+    }
+}
+
+/// Allows to access an observer after it was passed to an automaton.
+impl<G, S, T, O: Observer<G, S, T>> Observer<G, S, T> for Rc<RefCell<O>> {
+    fn before_step(&mut self, cells: &G) {
+        self.borrow_mut().before_step(cells);
+    }
+
+    fn after_step(&mut self, cells: &G, state: ExecutionState) {
+        self.borrow_mut().after_step(cells, state);
+    }
+
+    fn cell_changed(&mut self, pos: Pos<T>, old: Option<&S>, new: Option<&S>) {
+        self.borrow_mut().cell_changed(pos, old, new);
+    }
+
+    fn observes_cells(&self) -> bool {
+        self.borrow().observes_cells()
+    }
+}
+
+/// Observers subscribed to an automaton.
+pub(crate) struct Observers<G, S, T = usize> {
+    list: Vec<Box<dyn Observer<G, S, T>>>,
+    /// Whether any observer wants cell changes in the current step.
+    observes_cells: bool,
+    clone_fn: fn(&S) -> S,
+    eq_fn: fn(&S, &S) -> bool,
+}
+
+impl<G, S: Clone + PartialEq, T> Observers<G, S, T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            list: Vec::new(),
+            observes_cells: false,
+            clone_fn: S::clone,
+            eq_fn: S::eq,
+        }
+    }
+}
+
+impl<G, S, T> Observers<G, S, T> {
+    pub(crate) fn push(&mut self, observer: Box<dyn Observer<G, S, T>>) {
+        self.list.push(observer);
+    }
+
+    pub(crate) fn before_step(&mut self, cells: &G) {
+        self.observes_cells = self.list.iter().any(|observer| observer.observes_cells());
+
+        for observer in &mut self.list {
+            observer.before_step(cells);
+        }
+    }
+
+    pub(crate) fn after_step(&mut self, cells: &G, state: ExecutionState) {
+        for observer in &mut self.list {
+            observer.after_step(cells, state);
+        }
+    }
+
+    /// Returns a copy of a cell's state before it's evaluated, if any
+    /// observer needs it.
+    pub(crate) fn save_state(&self, state: &S) -> Option<S> {
+        self.observes_cells.then(|| (self.clone_fn)(state))
+    }
+
+    /// Notifies observers about an evaluated cell, if its state changed.
+    pub(crate) fn cell_evaluated(&mut self, pos: Pos<T>, old: Option<&S>, new: Option<&S>)
+    where
+        T: Copy,
+    {
+        let is_changed = match (old, new) {
+            (Some(old), Some(new)) => !(self.eq_fn)(old, new),
+            (old, new) => old.is_some() != new.is_some(),
+        };
+
+        if !self.observes_cells || !is_changed {
+            return;
+        }
+
+        for observer in &mut self.list {
+            if observer.observes_cells() {
+                observer.cell_changed(pos, old, new);
+            }
+        }
+    }
+}
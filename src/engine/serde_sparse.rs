@@ -0,0 +1,119 @@
+//! (De)serializes sparse grids as sequences of `(position, state)` pairs,
+//! since most formats don't support non-string map keys.
+//!
+//! Meant to be used with `#[serde(with = "...")]`.
+
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<K, V, H, Ser>(
+    grid: &HashMap<K, V, H>,
+    serializer: Ser,
+) -> Result<Ser::Ok, Ser::Error>
+where
+    K: Serialize,
+    V: Serialize,
+    Ser: Serializer,
+{
+    serializer.collect_seq(grid)
+}
+
+pub fn deserialize<'de, K, V, H, De>(deserializer: De) -> Result<HashMap<K, V, H>, De::Error>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    H: BuildHasher + Default,
+    De: Deserializer<'de>,
+{
+    Vec::<(K, V)>::deserialize(deserializer).map(|cells| cells.into_iter().collect())
+}
+
+/// Like the parent module, but for the keyframes of an optional
+/// [`SavedHistory`].
+pub mod history {
+    use std::{
+        collections::HashMap,
+        hash::{BuildHasher, Hash},
+    };
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::engine::history::{Frame, SavedHistory};
+
+    type Saved<K, V, H, D> = Option<SavedHistory<HashMap<K, V, H>, D>>;
+
+    /// Serializes a sparse grid as a sequence, see the parent module.
+    struct Cells<'a, K, V, H>(&'a HashMap<K, V, H>);
+
+    impl<K: Serialize, V: Serialize, H> Serialize for Cells<'_, K, V, H> {
+        fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+            super::serialize(self.0, serializer)
+        }
+    }
+
+    #[allow(clippy::ref_option, reason = "Required by `#[serde(with)]`")]
+    pub fn serialize<K, V, H, D, Ser>(
+        history: &Saved<K, V, H, D>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        D: Serialize,
+        Ser: Serializer,
+    {
+        history
+            .as_ref()
+            .map(|history| SavedHistory {
+                config: history.config,
+                frames: history
+                    .frames
+                    .iter()
+                    .map(|(generation, frame)| {
+                        let frame = match frame {
+                            Frame::Keyframe(cells) => Frame::Keyframe(Cells(cells)),
+                            Frame::Diff(diff) => Frame::Diff(diff),
+                        };
+
+                        (*generation, frame)
+                    })
+                    .collect(),
+                is_dirty: history.is_dirty,
+            })
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, K, V, H, D, De>(
+        deserializer: De,
+    ) -> Result<Saved<K, V, H, D>, De::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        H: BuildHasher + Default,
+        D: Deserialize<'de>,
+        De: Deserializer<'de>,
+    {
+        let history = Option::<SavedHistory<Vec<(K, V)>, D>>::deserialize(deserializer)?;
+
+        Ok(history.map(|history| SavedHistory {
+            config: history.config,
+            frames: history
+                .frames
+                .into_iter()
+                .map(|(generation, frame)| {
+                    let frame = match frame {
+                        Frame::Keyframe(cells) => Frame::Keyframe(cells.into_iter().collect()),
+                        Frame::Diff(diff) => Frame::Diff(diff),
+                    };
+
+                    (generation, frame)
+                })
+                .collect(),
+            is_dirty: history.is_dirty,
+        }))
+    }
+}
@@ -0,0 +1,408 @@
+//! Two-state, outer-totalistic rules (written as `B3/S23`), run on
+//! (potentially) infinitely-sized grids by the [`background`] engine,
+//! including rules with B0.
+//!
+//! Under a B0 rule every dead cell surrounded by dead cells comes alive,
+//! which the [`life_like`] engine can't model. The [`background`] engine
+//! instead stores the cells that differ from the background state, which
+//! follows the rule as well ("strobing"): rules without S8 make the
+//! background alternate between generations, rules with S8 keep it alive.
+//!
+//! # Examples
+//! ```rust
+//! use lifers::engine::totalistic::{Automaton, Rule};
+//!
+//! let rule = "B0123478/S01234678".parse::<Rule>().unwrap();
+//! let mut game = Automaton::new(rule, [((0, 0), ())].into_iter().collect(), None);
+//!
+//! game.step();
+//! assert!(game.background());
+//! assert!(game.is_alive((100, 100)));
+//! ```
+//!
+//! [`background`]: super::background
+//! [`life_like`]: super::life_like
+
+use rustc_hash::FxBuildHasher;
+use std::{fmt, hash::BuildHasher, str::FromStr};
+
+use crate::engine::{self, background, life_like, ExecutionState, Pos, Rect};
+
+/// Number of neighbors of a cell (in the Moore neighborhood).
+const NEIGHBORS_N: u32 = 8;
+
+/// A two-state, outer-totalistic rule in the Moore neighborhood.
+///
+/// Parsed from the `B3/S23` notation (or the older `23/3` one, which lists
+/// survival counts first) and displayed as the former.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rule {
+    /// Bit `n` is set if dead cells with `n` live neighbors come alive.
+    birth: u16,
+    /// Bit `n` is set if live cells with `n` live neighbors stay alive.
+    survival: u16,
+}
+
+impl Rule {
+    /// Conway's Game of Life (`B3/S23`).
+    pub const CONWAY: Self = Self {
+        birth: 1 << 3_u8,
+        survival: (1 << 2_u8) | (1 << 3_u8),
+    };
+
+    /// Creates a rule from the live neighbor counts that cause births and
+    /// survivals. Counts above 8 are ignored.
+    pub fn new<B, S>(birth: B, survival: S) -> Self
+    where
+        B: IntoIterator<Item = u32>,
+        S: IntoIterator<Item = u32>,
+    {
+        let bits = |counts: &mut dyn Iterator<Item = u32>| {
+            counts
+                .filter(|n| *n <= NEIGHBORS_N)
+                .fold(0_u16, |bits, n| bits | 1_u16.wrapping_shl(n))
+        };
+
+        Self {
+            birth: bits(&mut birth.into_iter()),
+            survival: bits(&mut survival.into_iter()),
+        }
+    }
+
+    /// Returns the next state of a cell with `neighbors_n` live neighbors.
+    pub const fn next(self, is_alive: bool, neighbors_n: u32) -> bool {
+        let bits = if is_alive { self.survival } else { self.birth };
+
+        neighbors_n <= NEIGHBORS_N && bits.wrapping_shr(neighbors_n) & 1 == 1
+    }
+
+    /// Returns `true` if the rule contains B0.
+    pub const fn has_b0(self) -> bool {
+        self.next(false, 0)
+    }
+
+    fn counts(bits: u16) -> impl Iterator<Item = u32> {
+        (0..=NEIGHBORS_N).filter(move |n| bits.wrapping_shr(*n) & 1 == 1)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B")?;
+
+        for n in Self::counts(self.birth) {
+            write!(f, "{n}")?;
+        }
+
+        write!(f, "/S")?;
+
+        for n in Self::counts(self.survival) {
+            write!(f, "{n}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Rule {
+    type Err = InvalidRule;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidRule(rule.to_owned());
+        let parse = |counts: &str| {
+            counts
+                .chars()
+                .map(|c| c.to_digit(10).filter(|n| *n <= NEIGHBORS_N))
+                .collect::<Option<Vec<_>>>()
+        };
+        let (first, second) = rule.trim().split_once('/').ok_or_else(invalid)?;
+
+        let (birth, survival) = match (first.chars().next(), second.chars().next()) {
+            (Some('B' | 'b'), Some('S' | 's')) => (&first[1..], &second[1..]),
+            (Some('S' | 's'), Some('B' | 'b')) => (&second[1..], &first[1..]),
+            // The `S/B` notation
+            _ => (second, first),
+        };
+
+        Ok(Self::new(
+            parse(birth).ok_or_else(invalid)?,
+            parse(survival).ok_or_else(invalid)?,
+        ))
+    }
+}
+
+/// An error returned when parsing a malformed [`Rule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidRule(pub String);
+
+impl fmt::Display for InvalidRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rule `{}`", self.0)
+    }
+}
+
+impl std::error::Error for InvalidRule {}
+
+/// An automaton running a [`Rule`] on the [`background`] engine.
+///
+/// See the [module-level documentation](self).
+pub struct Automaton<H = FxBuildHasher> {
+    automaton: background::Automaton<bool, u32, H>,
+    rule: Rule,
+}
+
+impl Automaton {
+    /// Creates an automaton with the given live cells on a dead background.
+    pub fn new(rule: Rule, cells: life_like::Grid<()>, generations_left: Option<u32>) -> Self {
+        Self::with_hasher(rule, cells, generations_left)
+    }
+}
+
+impl<H: BuildHasher + Default> Automaton<H> {
+    /// Like [`new()`](Automaton::new), but with cells hashed by `H` (see
+    /// [`hasher`](super::hasher)).
+    pub fn with_hasher(
+        rule: Rule,
+        cells: life_like::Grid<(), H>,
+        generations_left: Option<u32>,
+    ) -> Self {
+        let mut grid = background::Grid::with_hasher(false, H::default());
+        grid.extend(cells.into_keys().map(|pos| (pos, true)));
+
+        Self {
+            automaton: background::Automaton::without_step_fn(
+                grid,
+                1,
+                generations_left,
+                |pos, _, cells| background::count_neighbors(pos, 1, cells, |is_alive| *is_alive),
+            ),
+            rule,
+        }
+    }
+}
+
+impl<H> Automaton<H> {
+    pub const fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Returns `true` if the cells that aren't stored are alive.
+    pub const fn background(&self) -> bool {
+        *self.automaton.cells().background()
+    }
+
+    /// Returns the cells that differ from the background, i.e. the dead
+    /// cells if it's alive.
+    pub const fn cells(&self) -> &life_like::Grid<bool, H> {
+        self.automaton.cells().cells()
+    }
+
+    /// Returns the live cells, or `None` if there are infinitely many of them.
+    pub const fn live_cells(&self) -> Option<&life_like::Grid<bool, H>> {
+        if self.background() {
+            None
+        } else {
+            Some(self.cells())
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.automaton.is_finished()
+    }
+
+    /// Returns the number of generations computed so far.
+    pub const fn generation(&self) -> u64 {
+        self.automaton.generation()
+    }
+}
+
+impl<H: BuildHasher + Default> Automaton<H> {
+    /// Computes the next generation.
+    pub fn step(&mut self) -> ExecutionState {
+        let rule = self.rule;
+
+        self.automaton
+            .step_with(|_, is_alive, neighbors_n| rule.next(is_alive, neighbors_n))
+    }
+
+    /// Returns `true` if the cell at `pos` is alive.
+    pub fn is_alive(&self, pos: Pos<i64>) -> bool {
+        *self.automaton.get(pos)
+    }
+
+    /// Computes up to `generations` generations, stopping early if the
+    /// generation limit is reached.
+    ///
+    /// Returns the state returned by the last step.
+    pub fn run_for(&mut self, generations: u32) -> ExecutionState {
+        let mut state = ExecutionState::from_generations_left(engine::Automaton::generations_left(
+            &self.automaton,
+        ));
+
+        for _ in 0..generations {
+            if self.is_finished() {
+                break;
+            }
+
+            state = self.step();
+        }
+
+        state
+    }
+}
+
+impl<H: BuildHasher + Default> engine::Automaton for Automaton<H> {
+    type State = bool;
+    type Coord = i64;
+
+    fn step(&mut self) -> ExecutionState {
+        self.step()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.is_finished()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation()
+    }
+
+    fn generations_left(&self) -> Option<u32> {
+        engine::Automaton::generations_left(&self.automaton)
+    }
+
+    /// Returns whether the cell at `pos` is alive, which is never `None`.
+    fn get(&self, pos: Pos<i64>) -> Option<&bool> {
+        Some(self.automaton.get(pos))
+    }
+
+    /// Returns an iterator over the cells that differ from the background.
+    fn iter_cells(&self) -> impl Iterator<Item = (Pos<i64>, &bool)> {
+        self.automaton.cells().iter()
+    }
+
+    /// Returns the bounding box of the cells that differ from the
+    /// background.
+    fn bounding_box(&self) -> Option<Rect<i64>> {
+        Rect::from_positions(self.cells().keys().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Automaton, InvalidRule, Rule};
+    use crate::engine::{convert, life_like, ExecutionState, Rect};
+
+    const SIZE: usize = 32;
+
+    /// Steps a dense grid, treating cells outside of it as dead.
+    fn step_dense(rule: Rule, grid: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        (0..SIZE)
+            .map(|y| {
+                (0..SIZE)
+                    .map(|x| {
+                        let neighbors_n = (y.saturating_sub(1)..=y.saturating_add(1))
+                            .flat_map(|ny| {
+                                (x.saturating_sub(1)..=x.saturating_add(1)).map(move |nx| (nx, ny))
+                            })
+                            .filter(|(nx, ny)| (*nx, *ny) != (x, y))
+                            .filter(|(nx, ny)| {
+                                grid.get(*ny).and_then(|row| row.get(*nx)) == Some(&true)
+                            })
+                            .count();
+
+                        rule.next(grid[y][x], u32::try_from(neighbors_n).unwrap_or_default())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rules() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::CONWAY));
+        assert_eq!("23/3".parse(), Ok(Rule::CONWAY));
+        assert_eq!("s23/b3".parse(), Ok(Rule::CONWAY));
+        assert_eq!(Rule::CONWAY.to_string(), "B3/S23");
+        assert_eq!(
+            "B0/S".parse::<Rule>().map(|rule| rule.to_string()),
+            Ok("B0/S".to_owned())
+        );
+        assert_eq!(
+            "B9/S23".parse::<Rule>(),
+            Err(InvalidRule("B9/S23".to_owned()))
+        );
+        assert_eq!(
+            "B3S23".parse::<Rule>(),
+            Err(InvalidRule("B3S23".to_owned()))
+        );
+        assert!(Rule::new([0, 1], []).has_b0());
+    }
+
+    #[test]
+    fn conway() {
+        let mut game = Automaton::new(
+            Rule::CONWAY,
+            [((1, 0), ()), ((1, 1), ()), ((1, 2), ())]
+                .into_iter()
+                .collect(),
+            Some(2),
+        );
+
+        game.step();
+        assert_eq!(
+            game.live_cells(),
+            Some(
+                &[((0, 1), true), ((1, 1), true), ((2, 1), true)]
+                    .into_iter()
+                    .collect()
+            )
+        );
+        assert_eq!(game.run_for(5), ExecutionState::Remaining(0));
+        assert!(game.is_finished());
+        assert_eq!(game.generation(), 2);
+    }
+
+    #[test]
+    fn strobing() {
+        // Without S8 the background alternates, with S8 it stays alive
+        for (rule, backgrounds) in [
+            ("B013/S2", [true, false]),
+            ("B0123478/S01234678", [true, true]),
+        ] {
+            let rule = rule.parse::<Rule>().unwrap();
+            let r_pentomino: life_like::Grid<()> =
+                [(16, 15), (17, 15), (15, 16), (16, 16), (16, 17)]
+                    .into_iter()
+                    .map(|pos| (pos, ()))
+                    .collect();
+            let area = Rect::new((0, 0), (SIZE, SIZE));
+            let mut dense = convert::to_dense(&r_pentomino, area, |state| state.is_some());
+            let mut sparse = Automaton::new(rule, r_pentomino, None);
+
+            for generation in 0..6 {
+                dense = step_dense(rule, &dense);
+                sparse.step();
+
+                assert_eq!(sparse.background(), backgrounds[generation % 2]);
+            }
+
+            let background = sparse.background();
+            let sparse =
+                convert::to_dense(sparse.cells(), area, |state| state.is_some() != background);
+
+            // Only compare cells that the dead border of the dense grid
+            // couldn't affect yet
+            let inner = |grid: Vec<Vec<bool>>| -> Vec<Vec<bool>> {
+                grid.into_iter()
+                    .skip(6)
+                    .take(SIZE - 12)
+                    .map(|row| row.into_iter().skip(6).take(SIZE - 12).collect())
+                    .collect()
+            };
+
+            assert_eq!(inner(sparse), inner(dense));
+        }
+    }
+}
@@ -0,0 +1,562 @@
+//! Export of generations as images, for use without a screen.
+//!
+//! An [`ImageRenderer`] draws the cells of either engine inside of an area
+//! into an RGB [`Image`], with states mapped to colors via
+//! [`RenderCell<[u8; 3]>`](RenderCell). Images can be written as PPM, or as
+//! PNG with the `png` feature, and sequences of generations can be recorded
+//! into an animated GIF with the `gif` feature (see `GifRecorder`).
+//!
+//! # Examples
+//! ```rust
+//! use lifers::{
+//!     engine::{life_like::AutomatonBuilder, Rect},
+//!     frontend::image::ImageRenderer,
+//! };
+//!
+//! let game = AutomatonBuilder::new(1)
+//!     .init(|| [((1, 0), ()), ((1, 1), ()), ((1, 2), ())].into_iter().collect())
+//!     .run(|_, state, ()| state);
+//! let image = ImageRenderer::new(Rect::new((0, 0), (3, 3)), 4)
+//!     .grid_lines([128, 128, 128])
+//!     .render(&game);
+//!
+//! assert_eq!((image.width(), image.height()), (16, 16));
+//! assert_eq!(image.pixel(6, 1), Some([0, 0, 0]));
+//!
+//! let mut ppm = Vec::new();
+//! image.write_ppm(&mut ppm).unwrap();
+//! assert!(ppm.starts_with(b"P6\n16 16\n255\n"));
+//! ```
+
+#[cfg(feature = "gif")]
+use std::collections::{hash_map::Entry, HashMap};
+use std::io::{self, Write};
+
+use crate::{
+    engine::{Automaton, Coord, Rect},
+    frontend::RenderCell,
+};
+
+/// White, the color of cells that aren't stored by default.
+const WHITE: [u8; 3] = [255, 255, 255];
+
+/// Live cells are black, dead ones are white.
+impl RenderCell<[u8; 3]> for bool {
+    fn render_cell(&self) -> [u8; 3] {
+        if *self {
+            [0, 0, 0]
+        } else {
+            WHITE
+        }
+    }
+}
+
+/// Live cells are black.
+impl RenderCell<[u8; 3]> for () {
+    fn render_cell(&self) -> [u8; 3] {
+        [0, 0, 0]
+    }
+}
+
+/// An RGB image.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    /// The RGB values of the pixels, row by row.
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Creates an image filled with `color`.
+    pub fn new(width: usize, height: usize, color: [u8; 3]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: color.repeat(width.saturating_mul(height)),
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the RGB values of the pixels, row by row.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Returns the color of the pixel at `(x, y)`, or `None` if it's out of
+    /// bounds.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        if x >= self.width {
+            return None;
+        }
+
+        let i = self
+            .width
+            .saturating_mul(y)
+            .saturating_add(x)
+            .saturating_mul(3);
+
+        self.pixels
+            .get(i..i.saturating_add(3))
+            .and_then(|rgb| rgb.try_into().ok())
+    }
+
+    /// Fills a rectangle of pixels (clipped to the image) with `color`.
+    pub fn fill_rect(&mut self, rect: Rect, color: [u8; 3]) {
+        let x_end = rect.x.saturating_add(rect.width).min(self.width);
+
+        for y in rect.y..rect.y.saturating_add(rect.height).min(self.height) {
+            let row = self.width.saturating_mul(y);
+            let (start, end) = (row.saturating_add(rect.x), row.saturating_add(x_end));
+
+            if let Some(pixels) = self
+                .pixels
+                .get_mut(start.saturating_mul(3)..end.saturating_mul(3))
+            {
+                for pixel in pixels.chunks_exact_mut(3) {
+                    pixel.copy_from_slice(&color);
+                }
+            }
+        }
+    }
+
+    /// Writes the image in the binary PPM (`P6`) format.
+    ///
+    /// # Errors
+    /// Returns the errors of writing to `output`.
+    pub fn write_ppm<W: Write>(&self, mut output: W) -> io::Result<()> {
+        write!(output, "P6\n{} {}\n255\n", self.width, self.height)?;
+        output.write_all(&self.pixels)
+    }
+
+    /// Writes the image in the PNG format.
+    ///
+    /// # Errors
+    /// Returns the errors of the encoder, including the ones of writing to
+    /// `output`, or an [`io::ErrorKind::InvalidInput`] error if the image is
+    /// too big.
+    #[cfg(feature = "png")]
+    pub fn write_png<W: Write>(&self, output: W) -> Result<(), png::EncodingError> {
+        let (width, height) =
+            size(self.width, self.height).ok_or_else(|| png::EncodingError::IoError(too_big()))?;
+        let mut encoder = png::Encoder::new(output, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()
+    }
+}
+
+/// Renders the cells of an automaton with coordinates of type `T` (see
+/// [`Pos`](crate::engine::Pos)) inside of an area into [`Image`]s.
+///
+/// See the [module-level documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImageRenderer<T = usize> {
+    area: Rect<T>,
+    /// The width and height of a cell, in pixels.
+    cell_size: usize,
+    /// The color of cells that aren't stored (dead in sparse engines, out of
+    /// bounds in dense ones).
+    background: [u8; 3],
+    grid_lines: Option<[u8; 3]>,
+}
+
+impl<T: Coord> ImageRenderer<T> {
+    /// Creates a renderer for the cells inside of `area`, drawing each one as
+    /// a square of `cell_size` pixels.
+    pub const fn new(area: Rect<T>, cell_size: usize) -> Self {
+        Self {
+            area,
+            cell_size,
+            background: WHITE,
+            grid_lines: None,
+        }
+    }
+
+    /// Moves or resizes the rendered area.
+    pub const fn set_area(&mut self, area: Rect<T>) {
+        self.area = area;
+    }
+
+    /// Sets the color of cells that aren't stored (white by default).
+    pub const fn background(mut self, color: [u8; 3]) -> Self {
+        self.background = color;
+
+        self
+    }
+
+    /// Draws 1 pixel wide lines of `color` around every cell.
+    pub const fn grid_lines(mut self, color: [u8; 3]) -> Self {
+        self.grid_lines = Some(color);
+
+        self
+    }
+
+    /// Returns the size of the rendered images, in pixels.
+    pub const fn image_size(&self) -> (usize, usize) {
+        let line = self.grid_lines.is_some() as usize;
+        let pitch = self.cell_size.saturating_add(line);
+
+        (
+            self.area.width.saturating_mul(pitch).saturating_add(line),
+            self.area.height.saturating_mul(pitch).saturating_add(line),
+        )
+    }
+
+    /// Renders the current generation of `automaton`.
+    pub fn render<A>(&self, automaton: &A) -> Image
+    where
+        A: Automaton<Coord = T>,
+        A::State: RenderCell<[u8; 3]>,
+    {
+        let (width, height) = self.image_size();
+        let line = usize::from(self.grid_lines.is_some());
+        let pitch = self.cell_size.saturating_add(line);
+        let mut image = Image::new(width, height, self.grid_lines.unwrap_or(self.background));
+
+        for dy in 0..self.area.height {
+            for dx in 0..self.area.width {
+                let color = automaton
+                    .get((self.area.x.offset(dx), self.area.y.offset(dy)))
+                    .map_or(self.background, RenderCell::render_cell);
+                let corner = (
+                    dx.saturating_mul(pitch).saturating_add(line),
+                    dy.saturating_mul(pitch).saturating_add(line),
+                );
+
+                image.fill_rect(Rect::new(corner, (self.cell_size, self.cell_size)), color);
+            }
+        }
+
+        image
+    }
+}
+
+/// Records [`Image`]s of the same size into an animated GIF, which loops
+/// forever.
+///
+/// GIF frames have at most 256 colors, so images with more colors are
+/// approximated with a fixed palette.
+///
+/// # Examples
+/// ```rust
+/// use std::time::Duration;
+///
+/// use lifers::{
+///     engine::{life_like::AutomatonBuilder, Rect},
+///     frontend::image::{GifRecorder, ImageRenderer},
+/// };
+///
+/// let mut game = AutomatonBuilder::new(1)
+///     .init(|| [((1, 0), ()), ((1, 1), ()), ((1, 2), ())].into_iter().collect())
+///     .map(|pos, _, cells| lifers::engine::life_like::count_neighbors(pos, 1, cells))
+///     .run(|_, state, neighbors_n| match state {
+///         Some(()) => (2..=3).contains(&neighbors_n).then_some(()),
+///         None => (neighbors_n == 3).then_some(()),
+///     });
+/// let renderer = ImageRenderer::new(Rect::new((0, 0), (3, 3)), 8);
+/// let mut recorder = GifRecorder::new(Vec::new(), Duration::from_millis(200));
+///
+/// for _ in 0..4 {
+///     recorder.add_frame(&renderer.render(&game)).unwrap();
+///     game.step();
+/// }
+///
+/// let gif = recorder.finish().unwrap();
+/// assert!(gif.starts_with(b"GIF89a"));
+/// ```
+#[cfg(feature = "gif")]
+pub struct GifRecorder<W: Write> {
+    /// The output, until the first frame is added.
+    output: Option<W>,
+    encoder: Option<gif::Encoder<W>>,
+    /// The size of the frames, set by the first one.
+    size: (u16, u16),
+    /// The delay between frames, in hundredths of a second.
+    delay: u16,
+}
+
+#[cfg(feature = "gif")]
+impl<W: Write> GifRecorder<W> {
+    /// Creates a recorder that shows each frame for `delay` (rounded to
+    /// hundredths of a second).
+    pub fn new(output: W, delay: std::time::Duration) -> Self {
+        let mut recorder = Self {
+            output: Some(output),
+            encoder: None,
+            size: (0, 0),
+            delay: 0,
+        };
+        recorder.set_delay(delay);
+
+        recorder
+    }
+
+    /// Sets the delay of the following frames.
+    pub fn set_delay(&mut self, delay: std::time::Duration) {
+        self.delay = u16::try_from(delay.as_millis() / 10).unwrap_or(u16::MAX);
+    }
+
+    /// Adds a frame.
+    ///
+    /// # Errors
+    /// Returns the errors of the encoder, including the ones of writing to
+    /// the output, or an [`io::ErrorKind::InvalidInput`] error if the image
+    /// is too big or differs in size from the first one.
+    pub fn add_frame(&mut self, image: &Image) -> Result<(), gif::EncodingError> {
+        let (width, height) = size(image.width, image.height)
+            .and_then(|(width, height)| {
+                Some((u16::try_from(width).ok()?, u16::try_from(height).ok()?))
+            })
+            .ok_or_else(|| gif::EncodingError::Io(too_big()))?;
+
+        if let Some(output) = self.output.take() {
+            let mut encoder = gif::Encoder::new(output, width, height, &[])?;
+            encoder.set_repeat(gif::Repeat::Infinite)?;
+
+            self.encoder = Some(encoder);
+            self.size = (width, height);
+        }
+
+        if (width, height) != self.size {
+            return Err(gif::EncodingError::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "frame size differs from the first frame",
+            )));
+        }
+
+        let Some(encoder) = &mut self.encoder else {
+            return Err(gif::EncodingError::WriterNotFound);
+        };
+
+        let (pixels, palette) = index_colors(image);
+        let mut frame = gif::Frame::from_palette_pixels(width, height, pixels, palette, None);
+        frame.delay = self.delay;
+
+        encoder.write_frame(&frame)
+    }
+
+    /// Finishes the GIF, returning the output.
+    ///
+    /// # Errors
+    /// Returns the errors of writing to the output.
+    pub fn finish(self) -> Result<W, gif::EncodingError> {
+        match (self.encoder, self.output) {
+            (Some(encoder), _) => encoder.into_inner(),
+            // No frames were added
+            (None, Some(output)) => Ok(output),
+            (None, None) => Err(gif::EncodingError::WriterNotFound),
+        }
+    }
+}
+
+/// Converts the pixels of an image into palette indices, returning them along
+/// with the palette.
+///
+/// Images with more than 256 colors are approximated with the 6x6x6 color
+/// cube.
+#[cfg(feature = "gif")]
+fn index_colors(image: &Image) -> (Vec<u8>, Vec<u8>) {
+    let mut indices: HashMap<[u8; 3], u8> = HashMap::new();
+    let mut palette = Vec::new();
+    let mut pixels = Vec::with_capacity(image.pixels.len() / 3);
+
+    for rgb in image.pixels.chunks_exact(3) {
+        let index = match indices.entry([rgb[0], rgb[1], rgb[2]]) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => {
+                let Ok(index) = u8::try_from(palette.len() / 3) else {
+                    return index_cube(image);
+                };
+
+                palette.extend(entry.key());
+                *entry.insert(index)
+            }
+        };
+
+        pixels.push(index);
+    }
+
+    (pixels, palette)
+}
+
+/// Approximates the pixels of an image with the 6x6x6 color cube.
+#[cfg(feature = "gif")]
+fn index_cube(image: &Image) -> (Vec<u8>, Vec<u8>) {
+    let level =
+        |c: u8| u8::try_from(u16::from(c).saturating_mul(5).saturating_add(127) / 255).unwrap_or(5);
+    let pixels = image
+        .pixels
+        .chunks_exact(3)
+        .map(|rgb| {
+            level(rgb[0])
+                .saturating_mul(36)
+                .saturating_add(level(rgb[1]).saturating_mul(6))
+                .saturating_add(level(rgb[2]))
+        })
+        .collect();
+    let palette = (0..216_u8)
+        .flat_map(|i| [i / 36, i / 6 % 6, i % 6].map(|level| level.saturating_mul(51)))
+        .collect();
+
+    (pixels, palette)
+}
+
+/// Converts the size of an image for the encoders.
+#[cfg(any(feature = "png", feature = "gif"))]
+fn size(width: usize, height: usize) -> Option<(u32, u32)> {
+    u32::try_from(width).ok().zip(u32::try_from(height).ok())
+}
+
+#[cfg(any(feature = "png", feature = "gif"))]
+fn too_big() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "image is too big")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Image, ImageRenderer};
+    use crate::{
+        engine::{generic, Rect},
+        frontend::RenderCell,
+    };
+
+    #[derive(Clone, Copy, PartialEq)]
+    struct Heat(u8);
+
+    impl RenderCell<[u8; 3]> for Heat {
+        fn render_cell(&self) -> [u8; 3] {
+            [self.0, 0, 0]
+        }
+    }
+
+    fn game() -> generic::Automaton<Heat, ()> {
+        generic::Automaton::build((2, 2))
+            .init(|(x, y)| {
+                let heat = u8::try_from(x.saturating_add(y.saturating_mul(2))).unwrap_or_default();
+
+                Heat(heat.saturating_mul(50))
+            })
+            .run(|_, heat, ()| heat)
+    }
+
+    #[test]
+    fn render() {
+        let image = ImageRenderer::new(Rect::new((1, 0), (2, 2)), 2)
+            .grid_lines([9, 9, 9])
+            .background([1, 2, 3])
+            .render(&game());
+
+        assert_eq!((image.width(), image.height()), (7, 7));
+        assert_eq!(image.pixel(0, 0), Some([9, 9, 9]));
+        assert_eq!(image.pixel(1, 1), Some([50, 0, 0]));
+        assert_eq!(image.pixel(2, 2), Some([50, 0, 0]));
+        assert_eq!(image.pixel(3, 2), Some([9, 9, 9]));
+        // Out of bounds of the grid
+        assert_eq!(image.pixel(5, 5), Some([1, 2, 3]));
+        assert_eq!(image.pixel(1, 4), Some([150, 0, 0]));
+        assert_eq!(image.pixel(7, 0), None);
+    }
+
+    #[test]
+    fn ppm() {
+        let image = ImageRenderer::new(Rect::new((0, 0), (2, 1)), 1).render(&game());
+        let mut ppm = Vec::new();
+
+        image.write_ppm(&mut ppm).unwrap();
+        assert_eq!(ppm, b"P6\n2 1\n255\n\0\0\0\x32\0\0");
+        assert_eq!(Image::new(1, 1, [7, 7, 7]).pixels(), [7, 7, 7]);
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn png() {
+        let image = ImageRenderer::new(Rect::new((0, 0), (2, 2)), 3).render(&game());
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+
+        let decoded = png::Decoder::new(std::io::Cursor::new(png))
+            .read_info()
+            .and_then(|mut reader| {
+                let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+                reader.next_frame(&mut pixels)?;
+
+                Ok(pixels)
+            });
+
+        assert_eq!(decoded.ok().as_deref(), Some(image.pixels()));
+    }
+
+    #[test]
+    #[cfg(feature = "gif")]
+    fn gif() {
+        use std::time::Duration;
+
+        use super::GifRecorder;
+
+        let renderer = ImageRenderer::new(Rect::new((0, 0), (2, 2)), 2);
+        let mut recorder = GifRecorder::new(Vec::new(), Duration::from_millis(50));
+
+        for frame in 1..=3_u64 {
+            let image = renderer.render(&game());
+            recorder.set_delay(Duration::from_millis(frame.saturating_mul(50)));
+            recorder.add_frame(&image).unwrap();
+        }
+
+        assert!(recorder
+            .add_frame(&Image::new(1, 1, [0, 0, 0]))
+            .is_err_and(|error| matches!(error, gif::EncodingError::Io(_))));
+
+        let output = recorder.finish().unwrap();
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let frames: Vec<_> = options
+            .read_info(output.as_slice())
+            .into_iter()
+            .flat_map(|mut decoder| {
+                std::iter::from_fn(move || {
+                    let frame = decoder.read_next_frame().ok()??;
+
+                    Some((frame.delay, frame.buffer.get(..4).map(<[u8]>::to_vec)))
+                })
+            })
+            .collect();
+        let black = Some(vec![0, 0, 0, 255]);
+
+        assert_eq!(
+            frames,
+            [(5, black.clone()), (10, black.clone()), (15, black)]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "gif")]
+    fn palette() {
+        use super::index_colors;
+
+        // Black and 255 shades of red
+        let mut image = Image::new(257, 1, [0, 0, 0]);
+
+        for shade in 1..=255_u8 {
+            image.fill_rect(Rect::new((usize::from(shade), 0), (1, 1)), [shade, 0, 0]);
+        }
+
+        let (pixels, palette) = index_colors(&image);
+        assert_eq!(pixels.get(255..), Some([255, 0].as_slice()));
+        assert_eq!(palette.len(), 768);
+
+        // More than 256 colors fall back to the color cube
+        image.fill_rect(Rect::new((256, 0), (1, 1)), [0, 255, 0]);
+        assert_eq!(index_colors(&image).1.len(), 648);
+    }
+}
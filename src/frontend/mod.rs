@@ -0,0 +1,144 @@
+//! Frontend specification and helper traits and functions
+
+pub mod image;
+pub mod runner;
+pub mod terminal;
+pub mod unicode;
+
+use std::{
+    fmt::{Display, Write},
+    time::Duration,
+};
+
+use crate::engine::{Automaton, Coord, Pos, Rect};
+
+/// A simple helper function to quickly display a grid
+#[allow(clippy::arithmetic_side_effects, reason = "`String` concatenation")]
+pub fn render_grid_str<T: Display>(cells: &[Vec<T>]) -> String {
+    let mut output = String::new();
+
+    for xs in cells {
+        output += &xs.iter().fold(String::new(), |mut acc, cell| {
+            let () = write!(acc, "{cell}").unwrap();
+            acc
+        });
+
+        output += "\n";
+    }
+
+    output
+}
+
+/// Renders the cells of an automaton inside of `area` row by row, using
+/// `render` for each cell.
+///
+/// Cells that aren't stored (dead in sparse engines, out of bounds in dense
+/// ones) are passed to `render` as `None`.
+///
+/// # Examples
+/// ```rust
+/// use lifers::{
+///     engine::{life_like::AutomatonBuilder, Automaton},
+///     frontend::render_automaton_str,
+/// };
+///
+/// let game = AutomatonBuilder::new(1)
+///     .init(|| [((1, 0), ()), ((2, 1), ())].into_iter().collect())
+///     .run(|_, state, ()| state);
+/// let area = game.bounding_box().unwrap();
+///
+/// assert_eq!(
+///     render_automaton_str(&game, area, |state| if state.is_some() { '#' } else { '.' }),
+///     "#.\n.#\n"
+/// );
+/// ```
+pub fn render_automaton_str<A, T, F>(automaton: &A, area: Rect<A::Coord>, render: F) -> String
+where
+    A: Automaton,
+    T: Display,
+    F: Fn(Option<&A::State>) -> T,
+{
+    let mut output = String::new();
+
+    for dy in 0..area.height {
+        for dx in 0..area.width {
+            let pos = (area.x.offset(dx), area.y.offset(dy));
+            let _ = write!(output, "{}", render(automaton.get(pos)));
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+pub trait RenderCell<T> {
+    fn render_cell(&self) -> T;
+}
+
+/// An interface that shows an automaton and reports user input, driven by a
+/// [`Runner`](runner::Runner).
+///
+/// See the [`runner`] module for an example.
+pub trait Frontend<A: Automaton> {
+    type Error;
+
+    /// Draws the current generation of `automaton` as seen through `view`.
+    ///
+    /// # Errors
+    /// Returns the frontend's own errors (e.g. of drawing to the screen).
+    fn render(&mut self, automaton: &A, view: &View<A::Coord>) -> Result<(), Self::Error>;
+
+    /// Returns the events that happened since the last call.
+    ///
+    /// Waits for at most `timeout` if there are none yet (or until an event
+    /// happens, if it's `None`), which paces the runner.
+    ///
+    /// # Errors
+    /// Returns the frontend's own errors (e.g. of reading the input).
+    fn poll_events(&mut self, timeout: Option<Duration>) -> Result<Events<A>, Self::Error>;
+}
+
+/// The events reported by a [`Frontend`] of the automaton `A`.
+pub type Events<A> = Vec<Event<<A as Automaton>::Coord, <A as Automaton>::State>>;
+
+/// A user action reported by a [`Frontend`], for an automaton with
+/// coordinates of type `T` (see [`Pos`]) and states of type `S`.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Event<T = usize, S = bool> {
+    /// Stops the runner.
+    Quit,
+    /// Pauses or resumes the simulation.
+    TogglePause,
+    /// Computes a single generation (usually while paused).
+    Step,
+    /// Doubles the tick rate.
+    Faster,
+    /// Halves the tick rate.
+    Slower,
+    /// Sets the tick rate (in generations per second).
+    SetTickRate(f64),
+    /// Sets the state of a cell, `None` meaning dead (see
+    /// [`Runner::on_edit()`](runner::Runner::on_edit)).
+    Edit { pos: Pos<T>, state: Option<S> },
+    /// Moves the viewport by the given number of cells.
+    Pan { dx: i64, dy: i64 },
+    /// Doubles the zoom, showing half as many cells in each direction.
+    ZoomIn,
+    /// Halves the zoom, showing twice as many cells in each direction.
+    ZoomOut,
+}
+
+/// What a [`Frontend`] should show.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View<T = usize> {
+    /// The cells to show.
+    pub viewport: Rect<T>,
+    /// The size of a cell in the frontend's units (e.g. pixels), which stays
+    /// constant when multiplied by the size of the viewport.
+    pub zoom: u8,
+    pub paused: bool,
+    /// Generations per second.
+    pub tick_rate: f64,
+}
@@ -0,0 +1,358 @@
+//! A loop that drives any automaton with any [`Frontend`] at a target tick
+//! rate.
+//!
+//! The runner renders every generation, steps the automaton whenever a tick
+//! is due and handles the [`Event`]s reported by the frontend in between.
+//!
+//! # Examples
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use lifers::{
+//!     engine::{life_like, Automaton, Rect},
+//!     frontend::{runner::Runner, Event, Events, Frontend, View},
+//! };
+//!
+//! /// Quits after a number of frames.
+//! struct Headless(u64);
+//!
+//! impl<A: Automaton> Frontend<A> for Headless {
+//!     type Error = ();
+//!
+//!     fn render(&mut self, automaton: &A, _view: &View<A::Coord>) -> Result<(), ()> {
+//!         self.0 = automaton.generation();
+//!         Ok(())
+//!     }
+//!
+//!     fn poll_events(&mut self, timeout: Option<Duration>) -> Result<Events<A>, ()> {
+//!         std::thread::sleep(timeout.unwrap_or_default());
+//!
+//!         Ok(if self.0 == 3 { vec![Event::Quit] } else { Vec::new() })
+//!     }
+//! }
+//!
+//! let game = life_like::AutomatonBuilder::new(1)
+//!     .init(|| [((0, 0), ())].into_iter().collect())
+//!     .run(|_, state, ()| state);
+//! let mut runner = Runner::new(game, Rect::new((0, 0), (8, 8))).tick_rate(1000.0);
+//!
+//! runner.run(&mut Headless(0)).unwrap();
+//! assert_eq!(runner.automaton().generation(), 3);
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    engine::{Automaton, Coord, Pos, Rect},
+    frontend::{Event, Frontend, View},
+};
+
+/// The highest zoom, see [`View::zoom`].
+const MAX_ZOOM: u8 = 64;
+/// The lowest and highest tick rates (in generations per second).
+const TICK_RATES: (f64, f64) = (0.01, 10_000.0);
+
+pub type EditFn<A> = fn(&mut A, Pos<<A as Automaton>::Coord>, Option<<A as Automaton>::State>);
+
+/// Drives an automaton with a [`Frontend`].
+///
+/// See the [module-level documentation](self).
+pub struct Runner<A: Automaton> {
+    automaton: A,
+    view: View<A::Coord>,
+    edit_fn: Option<EditFn<A>>,
+    /// When the next generation is due.
+    next_tick: Instant,
+}
+
+impl<A: Automaton> Runner<A> {
+    /// Creates a runner showing the cells inside of `viewport`, running at 10
+    /// generations per second.
+    pub fn new(automaton: A, viewport: Rect<A::Coord>) -> Self {
+        Self {
+            automaton,
+            view: View {
+                viewport,
+                zoom: 1,
+                paused: false,
+                tick_rate: 10.0,
+            },
+            edit_fn: None,
+            next_tick: Instant::now(),
+        }
+    }
+
+    /// Sets the target number of generations per second. Rates that aren't
+    /// positive (or NaN) are ignored.
+    pub const fn tick_rate(mut self, tick_rate: f64) -> Self {
+        // NOTE: Also rejects NaN
+        if tick_rate > 0.0_f64 {
+            self.view.tick_rate = tick_rate.clamp(TICK_RATES.0, TICK_RATES.1);
+        }
+
+        self
+    }
+
+    /// Sets whether the runner starts paused.
+    pub const fn paused(mut self, paused: bool) -> Self {
+        self.view.paused = paused;
+
+        self
+    }
+
+    /// Sets the function that applies [`Event::Edit`]s, which are ignored
+    /// otherwise.
+    pub const fn on_edit(mut self, edit_fn: EditFn<A>) -> Self {
+        self.edit_fn = Some(edit_fn);
+
+        self
+    }
+
+    pub const fn automaton(&self) -> &A {
+        &self.automaton
+    }
+
+    pub const fn automaton_mut(&mut self) -> &mut A {
+        &mut self.automaton
+    }
+
+    pub fn into_automaton(self) -> A {
+        self.automaton
+    }
+
+    pub const fn view(&self) -> &View<A::Coord> {
+        &self.view
+    }
+
+    /// Runs the automaton until the frontend reports [`Event::Quit`] or the
+    /// generation limit is reached (after rendering the last generation).
+    ///
+    /// # Errors
+    /// Returns the first error of the frontend.
+    pub fn run<F: Frontend<A>>(&mut self, frontend: &mut F) -> Result<(), F::Error> {
+        self.schedule_tick(Instant::now());
+
+        loop {
+            frontend.render(&self.automaton, &self.view)?;
+
+            if self.automaton.is_finished() {
+                return Ok(());
+            }
+
+            let timeout = (!self.view.paused)
+                .then(|| self.next_tick.saturating_duration_since(Instant::now()));
+
+            for event in frontend.poll_events(timeout)? {
+                if matches!(event, Event::Quit) {
+                    return Ok(());
+                }
+
+                self.handle(event);
+            }
+
+            if !self.view.paused && Instant::now() >= self.next_tick {
+                self.automaton.step();
+                // Skip the ticks that were missed instead of catching up
+                self.schedule_tick(self.next_tick.max(Instant::now()));
+            }
+        }
+    }
+
+    /// Applies an event, returning `true` if it changed the automaton or the
+    /// view. [`Event::Quit`] is ignored.
+    pub fn handle(&mut self, event: Event<A::Coord, A::State>) -> bool {
+        let view = &mut self.view;
+
+        match event {
+            Event::Quit => return false,
+            Event::TogglePause => {
+                view.paused = !view.paused;
+                self.schedule_tick(Instant::now());
+            }
+            Event::Step => {
+                self.automaton.step();
+            }
+            Event::Faster => self.set_tick_rate(self.view.tick_rate * 2.0),
+            Event::Slower => self.set_tick_rate(self.view.tick_rate / 2.0),
+            Event::SetTickRate(tick_rate) => self.set_tick_rate(tick_rate),
+            Event::Edit { pos, state } => match self.edit_fn {
+                Some(edit_fn) => edit_fn(&mut self.automaton, pos, state),
+                None => return false,
+            },
+            Event::Pan { dx, dy } => {
+                view.viewport.x = shift(view.viewport.x, dx);
+                view.viewport.y = shift(view.viewport.y, dy);
+            }
+            Event::ZoomIn => {
+                if view.zoom >= MAX_ZOOM {
+                    return false;
+                }
+
+                view.zoom = view.zoom.saturating_mul(2);
+                view.viewport = scale(view.viewport, |size| size.div_ceil(2));
+            }
+            Event::ZoomOut => {
+                if view.zoom <= 1 {
+                    return false;
+                }
+
+                view.zoom /= 2;
+                view.viewport = scale(view.viewport, |size| size.saturating_mul(2));
+            }
+        }
+
+        true
+    }
+
+    fn set_tick_rate(&mut self, tick_rate: f64) {
+        // NOTE: Also rejects NaN
+        if tick_rate > 0.0_f64 {
+            self.view.tick_rate = tick_rate.clamp(TICK_RATES.0, TICK_RATES.1);
+            self.schedule_tick(Instant::now());
+        }
+    }
+
+    /// Schedules the next generation one tick after `from`.
+    fn schedule_tick(&mut self, from: Instant) {
+        let interval =
+            Duration::try_from_secs_f64(self.view.tick_rate.recip()).unwrap_or(Duration::MAX);
+
+        self.next_tick = from.checked_add(interval).unwrap_or(from);
+    }
+}
+
+/// Moves a coordinate by `distance` cells in either direction.
+fn shift<T: Coord>(coord: T, distance: i64) -> T {
+    let abs = usize::try_from(distance.unsigned_abs()).unwrap_or(usize::MAX);
+
+    if distance < 0 {
+        coord.offset_back(abs)
+    } else {
+        coord.offset(abs)
+    }
+}
+
+/// Resizes a rectangle with `resize`, keeping its center.
+fn scale<T: Coord, F: Fn(usize) -> usize>(rect: Rect<T>, resize: F) -> Rect<T> {
+    let (width, height) = (resize(rect.width).max(1), resize(rect.height).max(1));
+    let center = |coord: T, old: usize, new: usize| {
+        if new < old {
+            coord.offset(old.saturating_sub(new) / 2)
+        } else {
+            coord.offset_back(new.saturating_sub(old) / 2)
+        }
+    };
+
+    Rect::new(
+        (
+            center(rect.x, rect.width, width),
+            center(rect.y, rect.height, height),
+        ),
+        (width, height),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, thread, time::Duration};
+
+    use super::Runner;
+    use crate::{
+        engine::{life_like, Rect},
+        frontend::{Event, Frontend, View},
+    };
+
+    type Game = life_like::Automaton<()>;
+
+    /// Reports scripted events and records the views it rendered.
+    struct Scripted {
+        events: VecDeque<Vec<Event<i64, ()>>>,
+        views: Vec<(u64, View<i64>)>,
+    }
+
+    impl Frontend<Game> for Scripted {
+        type Error = ();
+
+        fn render(&mut self, automaton: &Game, view: &View<i64>) -> Result<(), ()> {
+            self.views.push((automaton.generation(), *view));
+
+            Ok(())
+        }
+
+        fn poll_events(&mut self, timeout: Option<Duration>) -> Result<Vec<Event<i64, ()>>, ()> {
+            let events = self.events.pop_front().ok_or(())?;
+
+            if events.is_empty() {
+                thread::sleep(timeout.unwrap_or_default());
+            }
+
+            Ok(events)
+        }
+    }
+
+    fn game() -> Game {
+        life_like::AutomatonBuilder::new(1)
+            .init(|| std::iter::once(((0, 0), ())).collect())
+            .generations(5)
+            .run(|_, state, ()| state)
+    }
+
+    #[test]
+    fn events() {
+        let mut runner = Runner::new(game(), Rect::new((0, 0), (8, 6)))
+            .paused(true)
+            .on_edit(|game, pos, state| {
+                game.set(pos, state);
+            });
+        let mut frontend = Scripted {
+            events: VecDeque::from([
+                vec![Event::Step, Event::Step],
+                vec![Event::Edit {
+                    pos: (-3, 2),
+                    state: Some(()),
+                }],
+                vec![Event::Pan { dx: -4, dy: 1 }, Event::ZoomIn],
+                vec![Event::ZoomOut, Event::ZoomOut, Event::Slower],
+                vec![Event::Quit, Event::Step],
+            ]),
+            views: Vec::new(),
+        };
+
+        assert_eq!(runner.run(&mut frontend), Ok(()));
+        assert_eq!(runner.automaton().generation(), 2);
+        assert_eq!(runner.automaton().get((-3, 2)), Some(&()));
+
+        let (generation, view) = frontend.views[3];
+        assert_eq!(generation, 2);
+        assert_eq!(view.zoom, 2);
+        assert_eq!(view.viewport, Rect::new((-2, 2), (4, 3)));
+
+        let view = runner.view();
+        assert_eq!(view.zoom, 1);
+        assert_eq!(view.viewport, Rect::new((-4, 1), (8, 6)));
+        assert!((view.tick_rate - 5.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn ticks() {
+        let mut runner = Runner::new(game(), Rect::new((0, 0), (8, 8))).tick_rate(1000.0);
+        let mut frontend = Scripted {
+            events: (0..10_u8).map(|_| Vec::new()).collect(),
+            views: Vec::new(),
+        };
+
+        // Stops at the generation limit
+        assert_eq!(runner.run(&mut frontend), Ok(()));
+        assert!(runner.automaton().is_finished());
+        assert_eq!(
+            frontend.views.last().map(|(generation, _)| *generation),
+            Some(5)
+        );
+
+        // Invalid rates are ignored
+        for tick_rate in [0.0_f64, -1.0_f64, f64::NAN] {
+            let runner = Runner::new(game(), Rect::new((0, 0), (8, 8))).tick_rate(tick_rate);
+            assert!((runner.view().tick_rate - 10.0).abs() < f64::EPSILON);
+        }
+    }
+}
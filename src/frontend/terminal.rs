@@ -0,0 +1,503 @@
+//! A frontend that draws automata to ANSI terminals.
+//!
+//! States are mapped to [`Glyph`]s (a character and optional colors) via
+//! [`RenderCell`]. A [`Terminal`] draws the cells inside of its viewport,
+//! optionally framed by a border and followed by a status line, and redraws
+//! subsequent frames in place by overwriting the previous one instead of
+//! clearing the screen, which avoids flicker.
+//!
+//! # Examples
+//! ```rust
+//! use lifers::{
+//!     engine::{life_like::AutomatonBuilder, Rect},
+//!     frontend::terminal::{ColorMode, Terminal},
+//! };
+//!
+//! let game = AutomatonBuilder::new(1)
+//!     .init(|| [((1, 0), true), ((1, 1), true), ((1, 2), true)].into_iter().collect())
+//!     .run(|_, state, ()| state);
+//! let terminal = Terminal::new(Rect::new((0, 0), (3, 3)), |is_alive: &bool| *is_alive)
+//!     .color_mode(ColorMode::Plain);
+//!
+//! assert_eq!(
+//!     terminal.render(&game),
+//!     "┌───┐\n│ █ │\n│ █ │\n│ █ │\n└───┘\nGeneration: 0 | Population: 3\n"
+//! );
+//! ```
+
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    engine::{Automaton, Coord, Rect},
+    frontend::{Events, Frontend, RenderCell, View},
+};
+
+/// The escape sequence that resets all styles.
+const RESET: &str = "\x1b[0m";
+
+/// A terminal color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Color {
+    /// One of the 256 indexed colors (the first 16 are the terminal's
+    /// palette).
+    Ansi256(u8),
+    /// A 24-bit color.
+    Rgb([u8; 3]),
+}
+
+impl Color {
+    /// Returns the closest indexed color, approximating 24-bit colors with
+    /// the 6x6x6 color cube.
+    pub fn to_ansi256(self) -> u8 {
+        match self {
+            Self::Ansi256(index) => index,
+            Self::Rgb(rgb) => {
+                let [r, g, b] = rgb.map(|c| {
+                    u8::try_from(u16::from(c).saturating_mul(5).saturating_add(127) / 255)
+                        .unwrap_or(5)
+                });
+
+                16_u8
+                    .saturating_add(r.saturating_mul(36))
+                    .saturating_add(g.saturating_mul(6))
+                    .saturating_add(b)
+            }
+        }
+    }
+
+    /// Writes the escape sequence that sets this color, as the foreground
+    /// (`layer` 38) or background (`layer` 48) color.
+    fn write_escape(self, output: &mut String, layer: u8, mode: ColorMode) {
+        let _ = match (self, mode) {
+            (_, ColorMode::Plain) => Ok(()),
+            (Self::Rgb([r, g, b]), ColorMode::TrueColor) => {
+                write!(output, "\x1b[{layer};2;{r};{g};{b}m")
+            }
+            (color, _) => write!(output, "\x1b[{layer};5;{}m", color.to_ansi256()),
+        };
+    }
+}
+
+/// How colors are written to the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ColorMode {
+    /// 24-bit colors are written as they are.
+    #[default]
+    TrueColor,
+    /// 24-bit colors are approximated with indexed ones, for terminals that
+    /// don't support them.
+    Ansi256,
+    /// No colors (or other escape sequences) are written.
+    Plain,
+}
+
+/// The look of a cell: a character with optional colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Glyph {
+    pub char: char,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+impl Glyph {
+    /// Creates a glyph with the terminal's default colors.
+    pub const fn new(char: char) -> Self {
+        Self {
+            char,
+            fg: None,
+            bg: None,
+        }
+    }
+
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+
+        self
+    }
+
+    pub const fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+
+        self
+    }
+
+    const fn has_colors(&self) -> bool {
+        self.fg.is_some() || self.bg.is_some()
+    }
+}
+
+impl Default for Glyph {
+    fn default() -> Self {
+        Self::new(' ')
+    }
+}
+
+impl RenderCell<Self> for Glyph {
+    fn render_cell(&self) -> Self {
+        *self
+    }
+}
+
+/// Live cells are full blocks, dead ones are blank.
+impl RenderCell<Glyph> for bool {
+    fn render_cell(&self) -> Glyph {
+        Glyph::new(if *self { '█' } else { ' ' })
+    }
+}
+
+/// Live cells are full blocks.
+impl RenderCell<Glyph> for () {
+    fn render_cell(&self) -> Glyph {
+        Glyph::new('█')
+    }
+}
+
+/// Draws the cells of an automaton with states of type `S` and coordinates
+/// of type `T` (see [`Pos`](crate::engine::Pos)) inside of a viewport.
+///
+/// See the [module-level documentation](self).
+#[derive(Debug, Clone)]
+pub struct Terminal<S, T = usize> {
+    viewport: Rect<T>,
+    /// Decides which cells count towards the population in the status line.
+    is_alive: fn(&S) -> bool,
+    /// The glyph of cells that aren't stored (dead in sparse engines, out of
+    /// bounds in dense ones).
+    empty: Glyph,
+    color_mode: ColorMode,
+    border: bool,
+    status_line: bool,
+    /// Number of lines of the last frame written by
+    /// [`draw()`](Self::draw).
+    lines_drawn: usize,
+}
+
+impl<S, T: Coord> Terminal<S, T> {
+    /// Creates a terminal frontend showing the cells inside of `viewport`,
+    /// with a border and a status line.
+    ///
+    /// `is_alive` decides which cells count towards the population.
+    pub fn new(viewport: Rect<T>, is_alive: fn(&S) -> bool) -> Self {
+        Self {
+            viewport,
+            is_alive,
+            empty: Glyph::default(),
+            color_mode: ColorMode::default(),
+            border: true,
+            status_line: true,
+            lines_drawn: 0,
+        }
+    }
+
+    pub const fn viewport(&self) -> Rect<T> {
+        self.viewport
+    }
+
+    /// Moves or resizes the viewport.
+    pub const fn set_viewport(&mut self, viewport: Rect<T>) {
+        self.viewport = viewport;
+    }
+
+    /// Sets the glyph of cells that aren't stored (blank by default).
+    pub const fn empty(mut self, glyph: Glyph) -> Self {
+        self.empty = glyph;
+
+        self
+    }
+
+    pub const fn color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+
+        self
+    }
+
+    pub const fn border(mut self, border: bool) -> Self {
+        self.border = border;
+
+        self
+    }
+
+    /// Sets whether the generation and population are shown below the
+    /// cells.
+    pub const fn status_line(mut self, status_line: bool) -> Self {
+        self.status_line = status_line;
+
+        self
+    }
+
+    /// Renders a frame, with every line ending in a newline.
+    pub fn render<A>(&self, automaton: &A) -> String
+    where
+        A: Automaton<State = S, Coord = T>,
+        S: RenderCell<Glyph>,
+    {
+        let Rect {
+            x,
+            y,
+            width,
+            height,
+        } = self.viewport;
+        let mut output = String::new();
+        let horizontal = "─".repeat(width);
+
+        if self.border {
+            let _ = writeln!(output, "┌{horizontal}┐");
+        }
+
+        for dy in 0..height {
+            if self.border {
+                output.push('│');
+            }
+
+            let mut style = None;
+
+            for dx in 0..width {
+                let glyph = automaton
+                    .get((x.offset(dx), y.offset(dy)))
+                    .map_or(self.empty, RenderCell::render_cell);
+
+                self.write_style(&mut output, &mut style, glyph);
+                output.push(glyph.char);
+            }
+
+            if style.is_some_and(|glyph: Glyph| glyph.has_colors()) {
+                output.push_str(RESET);
+            }
+
+            if self.border {
+                output.push('│');
+            }
+
+            output.push('\n');
+        }
+
+        if self.border {
+            let _ = writeln!(output, "└{horizontal}┘");
+        }
+
+        if self.status_line {
+            let _ = writeln!(
+                output,
+                "Generation: {} | Population: {}",
+                automaton.generation(),
+                automaton.live_cells(self.is_alive).count()
+            );
+        }
+
+        output
+    }
+
+    /// Writes a frame to `output`, overwriting the one written by the last
+    /// call (if any).
+    ///
+    /// Lines are overwritten and cleared up to their end rather than
+    /// clearing the whole screen, so the frame doesn't flicker.
+    ///
+    /// # Errors
+    /// Returns the errors of writing to `output`.
+    pub fn draw<A, W>(&mut self, automaton: &A, output: &mut W) -> io::Result<()>
+    where
+        A: Automaton<State = S, Coord = T>,
+        S: RenderCell<Glyph>,
+        W: Write,
+    {
+        let frame = Self::render(self, automaton);
+        let mut buffer = String::with_capacity(frame.len().saturating_mul(2));
+
+        if self.lines_drawn > 0 {
+            // Move to the start of the previous frame
+            let _ = write!(buffer, "\x1b[{}F", self.lines_drawn);
+        }
+
+        let mut lines_n = 0_usize;
+
+        for line in frame.lines() {
+            buffer.push_str(line);
+            // Clear the rest of the previous line
+            buffer.push_str("\x1b[K\n");
+            lines_n = lines_n.saturating_add(1);
+        }
+
+        // Clear the lines of a previous, longer frame
+        buffer.push_str("\x1b[J");
+        self.lines_drawn = lines_n;
+
+        output.write_all(buffer.as_bytes())?;
+        output.flush()
+    }
+
+    /// Forgets the last drawn frame, so that the next one is drawn below it.
+    pub const fn reset(&mut self) {
+        self.lines_drawn = 0;
+    }
+
+    /// Writes the escape sequences that switch from the `current` style to
+    /// the one of `glyph`, if they differ.
+    fn write_style(&self, output: &mut String, current: &mut Option<Glyph>, glyph: Glyph) {
+        if self.color_mode == ColorMode::Plain
+            || current.is_some_and(|style| style.fg == glyph.fg && style.bg == glyph.bg)
+            || (current.is_none() && !glyph.has_colors())
+        {
+            return;
+        }
+
+        if current.is_some_and(|style| style.has_colors()) {
+            output.push_str(RESET);
+        }
+
+        if let Some(fg) = glyph.fg {
+            fg.write_escape(output, 38, self.color_mode);
+        }
+
+        if let Some(bg) = glyph.bg {
+            bg.write_escape(output, 48, self.color_mode);
+        }
+
+        *current = Some(glyph);
+    }
+}
+
+/// Draws to the standard output, following the viewport of the runner.
+///
+/// Terminals don't report any events, so the automaton runs until it
+/// reaches its generation limit (or forever). Nothing could resume a paused
+/// runner, so polling without a timeout returns an error of kind
+/// [`io::ErrorKind::Unsupported`] instead of waiting forever.
+impl<A> Frontend<A> for Terminal<A::State, A::Coord>
+where
+    A: Automaton,
+    A::State: RenderCell<Glyph>,
+{
+    type Error = io::Error;
+
+    fn render(&mut self, automaton: &A, view: &View<A::Coord>) -> io::Result<()> {
+        self.set_viewport(view.viewport);
+        self.draw(automaton, &mut io::stdout().lock())
+    }
+
+    fn poll_events(&mut self, timeout: Option<Duration>) -> io::Result<Events<A>> {
+        let timeout = timeout.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "a terminal can't resume a paused runner",
+            )
+        })?;
+        thread::sleep(timeout);
+
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, time::Duration};
+
+    use super::{Color, ColorMode, Glyph, Terminal};
+    use crate::{
+        engine::{generic, Rect},
+        frontend::{Frontend, RenderCell},
+    };
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Cell {
+        Dead,
+        Red,
+        Blue,
+    }
+
+    impl RenderCell<Glyph> for Cell {
+        fn render_cell(&self) -> Glyph {
+            match self {
+                Self::Dead => Glyph::new('.'),
+                Self::Red => Glyph::new('#').fg(Color::Rgb([255, 0, 0])),
+                Self::Blue => Glyph::new('#').fg(Color::Ansi256(21)),
+            }
+        }
+    }
+
+    #[test]
+    fn colors() {
+        let game = generic::Automaton::build((4, 1))
+            .init(|(x, _)| [Cell::Red, Cell::Red, Cell::Dead, Cell::Blue][x])
+            .run(|_, cell, ()| cell);
+        let terminal = Terminal::new(Rect::new((0, 0), (4, 1)), |cell| *cell != Cell::Dead)
+            .border(false)
+            .status_line(false);
+
+        assert_eq!(
+            terminal.render(&game),
+            "\x1b[38;2;255;0;0m##\x1b[0m.\x1b[38;5;21m#\x1b[0m\n"
+        );
+        assert_eq!(
+            terminal.color_mode(ColorMode::Ansi256).render(&game),
+            "\x1b[38;5;196m##\x1b[0m.\x1b[38;5;21m#\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn viewport() {
+        let mut game = generic::Automaton::build((3, 3))
+            .init(|(x, y)| x == 1 && y == 1)
+            .run(|_, is_alive, ()| is_alive);
+        let mut terminal = Terminal::new(Rect::new((1, 1), (3, 2)), |is_alive: &bool| *is_alive)
+            .empty(Glyph::new('~'))
+            .color_mode(ColorMode::Plain);
+
+        game.step();
+        assert_eq!(
+            terminal.render(&game),
+            "┌───┐\n│█ ~│\n│  ~│\n└───┘\nGeneration: 1 | Population: 1\n"
+        );
+
+        terminal.set_viewport(Rect::new((0, 0), (1, 1)));
+        assert_eq!(
+            terminal.border(false).render(&game),
+            " \nGeneration: 1 | Population: 1\n"
+        );
+    }
+
+    #[test]
+    fn redraw() {
+        let game = generic::Automaton::build((2, 1))
+            .init(|(x, _)| x == 0)
+            .run(|_, is_alive, ()| is_alive);
+        let mut terminal = Terminal::new(Rect::new((0, 0), (2, 1)), |is_alive: &bool| *is_alive)
+            .border(false)
+            .status_line(false);
+        let mut output = Vec::new();
+
+        terminal.draw(&game, &mut output).unwrap();
+        terminal.draw(&game, &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(&output),
+            "█ \x1b[K\n\x1b[J\x1b[1F█ \x1b[K\n\x1b[J"
+        );
+    }
+
+    #[test]
+    fn poll_events() {
+        let mut terminal = Terminal::new(Rect::new((0, 0), (2, 1)), |is_alive: &bool| *is_alive);
+        let poll = |terminal: &mut Terminal<bool>, timeout| {
+            Frontend::<generic::Automaton<bool>>::poll_events(terminal, timeout)
+        };
+
+        assert!(poll(&mut terminal, Some(Duration::ZERO))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            poll(&mut terminal, None).map_err(|error| error.kind()),
+            Err(io::ErrorKind::Unsupported)
+        );
+    }
+}
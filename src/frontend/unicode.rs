@@ -0,0 +1,206 @@
+//! High-density rendering of two-state grids with Unicode block and Braille
+//! characters.
+//!
+//! Instead of one character per cell, [`Packing::HalfBlock`] packs 1x2 cells
+//! into each character (`▀`, `▄` and `█`) and [`Packing::Braille`] packs 2x4
+//! cells (`⠁` to `⣿`), so that large grids fit into a small terminal.
+//!
+//! # Examples
+//! ```rust
+//! use lifers::{
+//!     engine::{life_like::Grid, Rect},
+//!     frontend::unicode::{render_life_like, Packing},
+//! };
+//!
+//! let blinker: Grid<()> = [((0, -1), ()), ((0, 0), ()), ((0, 1), ())].into_iter().collect();
+//! let area = Rect::new((-1, -1), (3, 3));
+//!
+//! assert_eq!(render_life_like(&blinker, area, Packing::HalfBlock), " █ \n ▀ \n");
+//! assert_eq!(render_life_like(&blinker, area, Packing::Braille), "⠸⠀\n");
+//! ```
+
+use std::hash::BuildHasher;
+
+use crate::engine::{generic, life_like, Coord, Pos, Rect};
+
+/// How cells are packed into characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Packing {
+    /// One character per cell (`█` for live cells).
+    Block,
+    /// 1x2 cells per character.
+    #[default]
+    HalfBlock,
+    /// 2x4 cells per character.
+    Braille,
+}
+
+impl Packing {
+    /// Returns the number of cells (columns, rows) packed into a character.
+    pub const fn cell_size(self) -> (usize, usize) {
+        match self {
+            Self::Block => (1, 1),
+            Self::HalfBlock => (1, 2),
+            Self::Braille => (2, 4),
+        }
+    }
+
+    /// Returns the character for the cells of a block of [`cell_size()`],
+    /// where bit `x + y * columns` of `cells` is set for live cells.
+    ///
+    /// [`cell_size()`]: Self::cell_size
+    const fn char(self, cells: u8) -> char {
+        match self {
+            Self::Block => {
+                if cells == 0 {
+                    ' '
+                } else {
+                    '█'
+                }
+            }
+            Self::HalfBlock => match cells {
+                0 => ' ',
+                1 => '▀',
+                2 => '▄',
+                _ => '█',
+            },
+            Self::Braille => {
+                // Braille dots are numbered column by column, with the last
+                // row added later
+                let dots = (cells & 0b0000_0001)
+                    | (cells & 0b0000_0100).wrapping_shr(1)
+                    | (cells & 0b0001_0000).wrapping_shr(2)
+                    | (cells & 0b0000_0010).wrapping_shl(2)
+                    | (cells & 0b0000_1000).wrapping_shl(1)
+                    | (cells & 0b0010_0000)
+                    | (cells & 0b0100_0000)
+                    | (cells & 0b1000_0000);
+
+                match char::from_u32(0x2800_u32 | dots as u32) {
+                    Some(c) => c,
+                    None => ' ',
+                }
+            }
+        }
+    }
+}
+
+/// Renders the cells inside of `area` packed according to `packing`, with
+/// every line ending in a newline.
+///
+/// `is_alive` decides which positions are alive. Blocks that reach past the
+/// edges of `area` are padded with dead cells.
+pub fn render<T, F>(area: Rect<T>, packing: Packing, is_alive: F) -> String
+where
+    T: Coord,
+    F: Fn(Pos<T>) -> bool,
+{
+    let (columns, rows) = packing.cell_size();
+    let mut output = String::new();
+
+    for block_y in (0..area.height).step_by(rows) {
+        for block_x in (0..area.width).step_by(columns) {
+            let mut cells = 0_u8;
+
+            for (i, (dx, dy)) in (0..rows)
+                .flat_map(|dy| (0..columns).map(move |dx| (dx, dy)))
+                .enumerate()
+            {
+                let (x, y) = (block_x.saturating_add(dx), block_y.saturating_add(dy));
+
+                if x < area.width
+                    && y < area.height
+                    && is_alive((area.x.offset(x), area.y.offset(y)))
+                {
+                    cells |= 1_u8.wrapping_shl(u32::try_from(i).unwrap_or(u32::MAX));
+                }
+            }
+
+            output.push(packing.char(cells));
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Renders a whole dense grid, using `is_alive` for each cell.
+pub fn render_generic<S, F>(grid: &generic::Grid<S>, packing: Packing, is_alive: F) -> String
+where
+    F: Fn(&S) -> bool,
+{
+    let width = grid.first().map_or(0, Vec::len);
+    let area = Rect::new((0, 0), (width, grid.len()));
+
+    render(area, packing, |(x, y)| {
+        grid.get(y)
+            .and_then(|row| row.get(x))
+            .is_some_and(&is_alive)
+    })
+}
+
+/// Renders the cells of a sparse grid inside of `area`, where stored cells
+/// are alive.
+#[allow(
+    clippy::implicit_hasher,
+    reason = "False positive, `Grid` is already generic over its hasher"
+)]
+pub fn render_life_like<S, H: BuildHasher>(
+    grid: &life_like::Grid<S, H>,
+    area: Rect<i64>,
+    packing: Packing,
+) -> String {
+    render(area, packing, |pos| grid.contains_key(&pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_generic, render_life_like, Packing};
+    use crate::engine::{life_like, Rect};
+
+    #[test]
+    fn half_blocks() {
+        let grid = vec![
+            vec![false, true, false],
+            vec![false, false, true],
+            vec![true, true, true],
+        ];
+
+        assert_eq!(
+            render_generic(&grid, Packing::HalfBlock, |is_alive| *is_alive),
+            " ▀▄\n▀▀▀\n"
+        );
+        assert_eq!(
+            render_generic(&grid, Packing::Block, |is_alive| *is_alive),
+            " █ \n  █\n███\n"
+        );
+    }
+
+    #[test]
+    fn braille() {
+        let full: life_like::Grid<()> = Rect::new((-2, -4), (2, 4))
+            .positions()
+            .map(|pos| (pos, ()))
+            .collect();
+        let diagonal: life_like::Grid<()> = [(0, 0), (1, 1), (2, 2), (3, 3)]
+            .into_iter()
+            .map(|pos| (pos, ()))
+            .collect();
+
+        assert_eq!(
+            render_life_like(&full, Rect::new((-2, -4), (4, 4)), Packing::Braille),
+            "⣿⠀\n"
+        );
+        assert_eq!(
+            render_life_like(&diagonal, Rect::new((0, 0), (4, 4)), Packing::Braille),
+            "⠑⢄\n"
+        );
+        assert_eq!(
+            render_life_like(&diagonal, Rect::new((0, 0), (3, 5)), Packing::Braille),
+            "⠑⠄\n⠀⠀\n"
+        );
+    }
+}
@@ -4,7 +4,7 @@
 //! - **Engine:** Logic and algorithms behing the automata;
 //! - **API:** An interface for creating and interacting with created automata;
 //! - **Frontend(-s):** External interfaces that allow to represent simulation data
-//! in a different way (graphical, ASCII, etc.).
+//!   in a different way (graphical, ASCII, etc.).
 //!
 //! # Examples
 //! This is how one can implement Conway's Game of Life with `lifers`:
@@ -22,8 +22,8 @@
 //!     .map(|(x, y), _, cells| generic::count_neighbors(cells, (x, y), 1, |b| *b))
 //!     // Change cells' state depending on the number of neighbors
 //!     .run(|_, is_alive, neighbors_n| match is_alive {
-//!         true => (2..=3).contains(neighbors_n),
-//!         false => *neighbors_n == 3,
+//!         true => (2..=3).contains(&neighbors_n),
+//!         false => neighbors_n == 3,
 //!     });
 //!
 //! // Compute the next generation
@@ -58,7 +58,12 @@
     clippy::return_self_not_must_use,
     clippy::missing_docs_in_private_items,
     clippy::std_instead_of_core,
-    clippy::pub_use
+    clippy::pub_use,
+    reason = "Too noisy for this crate's style"
+)]
+#![allow(
+    clippy::zero_sized_map_values,
+    reason = "`Grid<()>` is the canonical two-state grid"
 )]
 
 // TODO:
@@ -69,16 +74,18 @@
 // - [x] API (builder, stepping, frontend creation..)
 // - [x] Frontend
 //   - [x] Raylib Frontend
-//   - [-] ASCII Frontend
+//   - [x] ASCII Frontend
 
+pub mod analysis;
 pub mod engine;
 pub mod frontend;
 mod macros;
+pub mod pattern;
 
 /// Helper module with all common imports
 pub mod prelude {
     pub use crate::{
         engine::{generic, life_like},
-        frontend::RenderCell,
+        frontend::{Frontend, RenderCell},
     };
 }
@@ -0,0 +1,227 @@
+//! The [Life 1.05](https://conwaylife.com/wiki/Life_1.05) pattern format.
+//!
+//! Patterns consist of `#P x y` blocks of `.`/`*` rows, placed relative to
+//! the origin. Rules are given either as `#N` (Conway's Game of Life) or as
+//! `#R survival/birth`, and are converted to and from the `B3/S23` notation.
+
+use std::fmt::Write;
+
+use crate::pattern::{
+    numbered_lines, offset, parse_number, read_life_header, ParseError, ParseErrorKind, Pattern,
+};
+
+/// First line of every Life 1.05 file.
+pub const HEADER: &str = "#Life 1.05";
+
+/// Rule assumed by `#N`.
+const NORMAL_RULE: &str = "B3/S23";
+
+/// Parses a Life 1.05 pattern.
+///
+/// Rows before the first `#P` line are placed at the origin.
+///
+/// # Errors
+/// Returns an error if the header is missing or malformed, a `#P` or `#R`
+/// line is malformed, or a row contains a character other than `.` and `*`.
+pub fn read(input: &str) -> Result<Pattern, ParseError> {
+    let mut lines = numbered_lines(input);
+    read_life_header(&mut lines, HEADER, input)?;
+
+    let mut pattern = Pattern::default();
+    let mut cells = Vec::new();
+    let (mut block, mut y) = ((0, 0), 0_usize);
+
+    for (n, line) in lines {
+        if let Some(line) = line.strip_prefix('#') {
+            if let Some(position) = line.strip_prefix('P') {
+                block = read_position(position, n)?;
+                y = 0;
+            } else {
+                read_metadata(&mut pattern, line, n)?;
+            }
+
+            continue;
+        }
+
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                '.' => (),
+                '*' => cells.push(((offset(block.0, x), offset(block.1, y)), 1)),
+                c => return Err(ParseError::new(n, ParseErrorKind::UnexpectedChar(c))),
+            }
+        }
+
+        y = y.saturating_add(1);
+    }
+
+    pattern.set_absolute_cells(cells);
+
+    Ok(pattern)
+}
+
+/// Writes a pattern in the Life 1.05 format, as a single block.
+///
+/// Rules that aren't in the `Bx/Sy` notation are left out.
+pub fn write(pattern: &Pattern) -> String {
+    let mut output = format!("{HEADER}\n");
+
+    write_metadata(&mut output, pattern);
+
+    let _ = writeln!(output, "#P {} {}", pattern.origin.0, pattern.origin.1);
+
+    let rows = pattern.rows();
+
+    for y in 0..pattern.size.1 {
+        let mut x = 0;
+
+        for (cell_x, _) in rows.get(&y).into_iter().flatten() {
+            output.extend((x..*cell_x).map(|_| '.'));
+            output.push('*');
+            x = cell_x.saturating_add(1);
+        }
+
+        // Empty rows still need a character
+        if x == 0 {
+            output.push('.');
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Reads a `#` line other than `#P` (without the `#`).
+pub(crate) fn read_metadata(pattern: &mut Pattern, line: &str, n: usize) -> Result<(), ParseError> {
+    let (tag, value) = line.split_once(' ').unwrap_or((line, ""));
+
+    match tag {
+        "D" => pattern.read_comment(value),
+        "N" => pattern.rule = Some(NORMAL_RULE.to_owned()),
+        "R" => {
+            let (survival, birth) = value
+                .trim()
+                .split_once('/')
+                .filter(|(survival, birth)| is_rule_part(survival) && is_rule_part(birth))
+                .ok_or_else(|| {
+                    ParseError::new(n, ParseErrorKind::InvalidLine(format!("#{line}")))
+                })?;
+
+            pattern.rule = Some(format!("B{birth}/S{survival}"));
+        }
+        // Other lines are not meaningful for us
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Writes the `#D`, `#N` and `#R` lines of a pattern.
+pub(crate) fn write_metadata(output: &mut String, pattern: &Pattern) {
+    for comment in pattern.comment_lines() {
+        let _ = writeln!(output, "#D {comment}");
+    }
+
+    match pattern.rule.as_deref() {
+        Some(NORMAL_RULE) => output.push_str("#N\n"),
+        Some(rule) => {
+            let rule = rule
+                .strip_prefix('B')
+                .and_then(|rule| rule.split_once("/S"))
+                .filter(|(birth, survival)| is_rule_part(birth) && is_rule_part(survival));
+
+            if let Some((birth, survival)) = rule {
+                let _ = writeln!(output, "#R {survival}/{birth}");
+            }
+        }
+        None => (),
+    }
+}
+
+/// Reads the position of a `#P` line (without the `#P`).
+fn read_position(position: &str, n: usize) -> Result<(i64, i64), ParseError> {
+    match *position.split_whitespace().collect::<Vec<_>>() {
+        [x, y] => Ok((parse_number(x, n)?, parse_number(y, n)?)),
+        _ => Err(ParseError::new(
+            n,
+            ParseErrorKind::InvalidLine(format!("#P{position}")),
+        )),
+    }
+}
+
+fn is_rule_part(part: &str) -> bool {
+    part.chars().all(|c| matches!(c, '0'..='8'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read, write};
+    use crate::pattern::{ParseError, ParseErrorKind, Pattern};
+
+    #[test]
+    fn blocks() {
+        let pattern = read(
+            "#Life 1.05\n#D Name: Two blinkers\n#D Far apart\n#R 23/36\n#P -1 -1\n***\n#P 3 -2\n*\n*\n*\n",
+        )
+        .unwrap();
+
+        assert_eq!(pattern.name.as_deref(), Some("Two blinkers"));
+        assert_eq!(pattern.comments, ["Far apart"]);
+        assert_eq!(pattern.rule.as_deref(), Some("B36/S23"));
+        assert_eq!(pattern.origin, (-1, -2));
+        assert_eq!(pattern.size, (5, 3));
+        assert_eq!(
+            pattern.to_generic(),
+            [
+                [false, false, false, false, true],
+                [true, true, true, false, true],
+                [false, false, false, false, true]
+            ]
+        );
+        assert_eq!(read(&write(&pattern)).unwrap(), pattern);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut glider = Pattern::new([((1, 0), 1), ((2, 1), 1), ((0, 3), 1), ((1, 3), 1)]);
+        glider.rule = Some("B3/S23".to_owned());
+
+        let output = write(&glider);
+
+        assert_eq!(output, "#Life 1.05\n#N\n#P 0 0\n.*\n..*\n.\n**\n");
+        assert_eq!(read(&output).unwrap(), glider);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            read("#P 0 0\n*"),
+            Err(ParseError::new(1, ParseErrorKind::MissingHeader))
+        );
+        assert_eq!(
+            read("#Life 1.06\n0 0"),
+            Err(ParseError::new(
+                1,
+                ParseErrorKind::InvalidHeader("#Life 1.06".to_owned())
+            ))
+        );
+        assert_eq!(
+            read("#Life 1.05\n#P 0\n*"),
+            Err(ParseError::new(
+                2,
+                ParseErrorKind::InvalidLine("#P 0".to_owned())
+            ))
+        );
+        assert_eq!(
+            read("#Life 1.05\n#P 0 x\n*"),
+            Err(ParseError::new(
+                2,
+                ParseErrorKind::InvalidNumber("x".to_owned())
+            ))
+        );
+        assert_eq!(
+            read("#Life 1.05\n.*O"),
+            Err(ParseError::new(2, ParseErrorKind::UnexpectedChar('O')))
+        );
+    }
+}
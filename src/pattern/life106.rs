@@ -0,0 +1,122 @@
+//! The [Life 1.06](https://conwaylife.com/wiki/Life_1.06) pattern format.
+//!
+//! Patterns are lists of `x y` coordinates of alive cells. Metadata is read
+//! and written using the `#D`, `#N` and `#R` lines of
+//! [Life 1.05](crate::pattern::life105), which most readers accept.
+
+use std::fmt::Write;
+
+use crate::pattern::{
+    life105, numbered_lines, parse_number, read_life_header, ParseError, ParseErrorKind, Pattern,
+};
+
+/// First line of every Life 1.06 file.
+pub const HEADER: &str = "#Life 1.06";
+
+/// Parses a Life 1.06 pattern.
+///
+/// # Errors
+/// Returns an error if the header is missing or malformed, or a line isn't
+/// a pair of coordinates.
+pub fn read(input: &str) -> Result<Pattern, ParseError> {
+    let mut lines = numbered_lines(input);
+    read_life_header(&mut lines, HEADER, input)?;
+
+    let mut pattern = Pattern::default();
+    let mut cells = Vec::new();
+
+    for (n, line) in lines {
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(line) = line.strip_prefix('#') {
+            life105::read_metadata(&mut pattern, line, n)?;
+            continue;
+        }
+
+        match *line.split_whitespace().collect::<Vec<_>>() {
+            [x, y] => cells.push(((parse_number(x, n)?, parse_number(y, n)?), 1)),
+            _ => {
+                return Err(ParseError::new(
+                    n,
+                    ParseErrorKind::InvalidLine(line.to_owned()),
+                ))
+            }
+        }
+    }
+
+    pattern.set_absolute_cells(cells);
+
+    Ok(pattern)
+}
+
+/// Writes a pattern in the Life 1.06 format, in reading order.
+pub fn write(pattern: &Pattern) -> String {
+    let mut output = format!("{HEADER}\n");
+    let mut cells: Vec<_> = pattern.absolute_cells().map(|(pos, _)| pos).collect();
+
+    life105::write_metadata(&mut output, pattern);
+    cells.sort_unstable_by_key(|(x, y)| (*y, *x));
+
+    for (x, y) in cells {
+        let _ = writeln!(output, "{x} {y}");
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read, write};
+    use crate::pattern::{ParseError, ParseErrorKind};
+
+    const GLIDER: &str = "#Life 1.06\n#D Name: Glider\n#N\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+
+    #[test]
+    fn glider() {
+        let glider = read(GLIDER).unwrap();
+
+        assert_eq!(glider.name.as_deref(), Some("Glider"));
+        assert_eq!(glider.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(glider.origin, (-1, -1));
+        assert_eq!(
+            glider.to_generic(),
+            [
+                [false, true, false],
+                [false, false, true],
+                [true, true, true]
+            ]
+        );
+        assert_eq!(write(&glider), GLIDER);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            read(""),
+            Err(ParseError::new(0, ParseErrorKind::MissingHeader))
+        );
+        assert_eq!(
+            read("#Life 1.06\n0 0\n1 2 3"),
+            Err(ParseError::new(
+                3,
+                ParseErrorKind::InvalidLine("1 2 3".to_owned())
+            ))
+        );
+        assert_eq!(
+            read("#Life 1.06\n0 1.5"),
+            Err(ParseError::new(
+                2,
+                ParseErrorKind::InvalidNumber("1.5".to_owned())
+            ))
+        );
+        assert_eq!(
+            read("#Life 1.06\n#R 23\n0 0"),
+            Err(ParseError::new(
+                2,
+                ParseErrorKind::InvalidLine("#R 23".to_owned())
+            ))
+        );
+    }
+}
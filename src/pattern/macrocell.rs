@@ -0,0 +1,526 @@
+//! Golly's [macrocell](https://conwaylife.com/wiki/Macrocell) (`.mc`) pattern
+//! format.
+//!
+//! Macrocell files describe a pattern as a deduplicated quadtree, which
+//! keeps huge but repetitive patterns small. The root node is centered on
+//! the origin, so patterns keep their position.
+//!
+//! Two-state patterns use 8x8 leaves (`.`/`*`/`$` lines), while multistate
+//! patterns use level 1 nodes containing four states.
+//!
+//! # Performance
+//! [`Pattern`]s store every cell, so reading expands the quadtree and takes
+//! time and memory proportional to the number of cells rather than the size
+//! of the file. To guard against files that expand to billions of cells,
+//! [`read()`] gives up after [`DEFAULT_MAX_CELLS`], see [`read_with_limit()`]
+//! for a custom limit.
+
+use std::{collections::HashMap, fmt::Write};
+
+use crate::pattern::{numbered_lines, offset, parse_number, ParseError, ParseErrorKind, Pattern};
+
+/// Prefix of the first line of every macrocell file.
+pub const HEADER: &str = "[M2]";
+
+/// Highest quadtree level whose cells all have `i64` coordinates.
+const MAX_LEVEL: u32 = 63;
+
+/// Level of the 8x8 leaves of two-state patterns.
+const LEAF_LEVEL: u32 = 3;
+
+/// Side length of the 8x8 leaves.
+const LEAF_SIZE: usize = 8;
+
+/// Maximum number of cells read by [`read()`].
+pub const DEFAULT_MAX_CELLS: usize = 1 << 24;
+
+/// Parses a macrocell pattern with at most [`DEFAULT_MAX_CELLS`] cells.
+///
+/// # Errors
+/// See [`read_with_limit()`].
+pub fn read(input: &str) -> Result<Pattern, ParseError> {
+    read_with_limit(input, DEFAULT_MAX_CELLS)
+}
+
+/// Parses a macrocell pattern with at most `max_cells` cells.
+///
+/// # Errors
+/// Returns an error if the header is missing, a node is malformed or
+/// refers to a node that doesn't exist or has the wrong level, or the
+/// pattern has more than `max_cells` cells.
+pub fn read_with_limit(input: &str, max_cells: usize) -> Result<Pattern, ParseError> {
+    let mut lines = numbered_lines(input).filter(|(_, line)| !line.is_empty());
+
+    match lines.next() {
+        Some((_, line)) if line.starts_with(HEADER) => (),
+        Some((n, _)) => return Err(ParseError::new(n, ParseErrorKind::MissingHeader)),
+        None => {
+            return Err(ParseError::new(
+                input.lines().count(),
+                ParseErrorKind::MissingHeader,
+            ))
+        }
+    }
+
+    let mut pattern = Pattern::default();
+    let mut nodes = Vec::new();
+    // Number of cells in each node and the line of the last one
+    let (mut counts, mut last_line) = (Vec::new(), 0);
+
+    for (n, line) in lines {
+        if let Some(line) = line.strip_prefix('#') {
+            let (tag, value) = line.split_once(' ').unwrap_or((line, ""));
+
+            match tag {
+                "R" => pattern.rule = Some(value.trim().to_owned()),
+                "C" => pattern.read_comment(value),
+                // Other lines (generation, view, etc.) are not meaningful for us
+                _ => (),
+            }
+        } else {
+            let node = Node::read(line, n, &nodes)?;
+            counts.push(node.count_cells(&counts));
+            nodes.push(node);
+            last_line = n;
+        }
+    }
+
+    if counts.last().is_some_and(|count| *count > max_cells) {
+        return Err(ParseError::new(
+            last_line,
+            ParseErrorKind::TooManyCells(max_cells),
+        ));
+    }
+
+    let mut cells = Vec::new();
+
+    if let Some(root) = nodes.last() {
+        let corner = half_size(root.level()).wrapping_neg();
+        root.expand(&nodes, (corner, corner), &mut cells);
+    }
+
+    pattern.set_absolute_cells(cells);
+
+    Ok(pattern)
+}
+
+/// Writes a pattern in the macrocell format.
+///
+/// Cells further than 2<sup>62</sup> from the origin can't be represented
+/// and are left out.
+pub fn write(pattern: &Pattern) -> String {
+    let multistate = pattern.is_multistate();
+    let root_range = half_size(MAX_LEVEL).wrapping_neg()..half_size(MAX_LEVEL);
+    let cells: Vec<_> = pattern
+        .absolute_cells()
+        .filter(|((x, y), _)| root_range.contains(x) && root_range.contains(y))
+        .collect();
+
+    let mut level = if multistate { 1 } else { LEAF_LEVEL };
+
+    while level < MAX_LEVEL
+        && !cells.iter().all(|((x, y), _)| {
+            let range = half_size(level).wrapping_neg()..half_size(level);
+            range.contains(x) && range.contains(y)
+        })
+    {
+        level = level.saturating_add(1);
+    }
+
+    let mut writer = Writer {
+        multistate,
+        lines: Vec::new(),
+        indices: HashMap::new(),
+    };
+    let corner = half_size(level).wrapping_neg();
+    writer.node(cells, (corner, corner), level);
+
+    let mut output = format!("{HEADER} (lifers {})\n", env!("CARGO_PKG_VERSION"));
+
+    if let Some(rule) = &pattern.rule {
+        let _ = writeln!(output, "#R {rule}");
+    }
+
+    for comment in pattern.comment_lines() {
+        let _ = writeln!(output, "#C {comment}");
+    }
+
+    for line in writer.lines {
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Returns half of the side length of a node.
+///
+/// `level` must be between 1 and [`MAX_LEVEL`].
+const fn half_size(level: u32) -> i64 {
+    1_i64.wrapping_shl(level.wrapping_sub(1))
+}
+
+/// Returns the index of a quadrant in the northwest, northeast, southwest,
+/// southeast order.
+const fn quadrant(is_east: bool, is_south: bool) -> usize {
+    match (is_east, is_south) {
+        (false, false) => 0,
+        (true, false) => 1,
+        (false, true) => 2,
+        (true, true) => 3,
+    }
+}
+
+/// A quadtree node, as read from a single line.
+enum Node {
+    /// A node whose cells are given directly (8x8 leaves and multistate
+    /// level 1 nodes).
+    Cells {
+        level: u32,
+        cells: Vec<((usize, usize), u8)>,
+    },
+    /// A node made of four smaller ones (northwest, northeast, southwest
+    /// and southeast), with `0` being an empty node.
+    Inner { level: u32, children: [usize; 4] },
+}
+
+impl Node {
+    /// Parses a node, checking that its children are among `nodes`.
+    fn read(line: &str, n: usize, nodes: &[Self]) -> Result<Self, ParseError> {
+        let invalid = || ParseError::new(n, ParseErrorKind::InvalidLine(line.to_owned()));
+
+        if line.starts_with(['.', '*', '$']) {
+            let (mut x, mut y) = (0_usize, 0_usize);
+            let mut cells = Vec::new();
+
+            for c in line.chars() {
+                match c {
+                    '.' => x = x.saturating_add(1),
+                    '*' => {
+                        if x >= LEAF_SIZE || y >= LEAF_SIZE {
+                            return Err(invalid());
+                        }
+
+                        cells.push(((x, y), 1));
+                        x = x.saturating_add(1);
+                    }
+                    '$' => {
+                        x = 0;
+                        y = y.saturating_add(1);
+                    }
+                    c => return Err(ParseError::new(n, ParseErrorKind::UnexpectedChar(c))),
+                }
+
+                if x > LEAF_SIZE || y > LEAF_SIZE {
+                    return Err(invalid());
+                }
+            }
+
+            return Ok(Self::Cells {
+                level: LEAF_LEVEL,
+                cells,
+            });
+        }
+
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let [level, values @ ..] = fields.as_slice() else {
+            return Err(invalid());
+        };
+        let level: u32 = parse_number(level, n)?;
+        let values: [&str; 4] = values.try_into().map_err(|_| invalid())?;
+
+        if level == 1 {
+            let mut cells = Vec::new();
+
+            for (pos, state) in [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter().zip(values) {
+                cells.push((pos, parse_number(state, n)?));
+            }
+
+            return Ok(Self::Cells { level, cells });
+        }
+
+        if !(2..=MAX_LEVEL).contains(&level) {
+            return Err(invalid());
+        }
+
+        let mut children = [0_usize; 4];
+
+        for (child, index) in children.iter_mut().zip(values) {
+            *child = parse_number(index, n)?;
+
+            let is_valid = *child == 0
+                || nodes
+                    .get((*child).wrapping_sub(1))
+                    .is_some_and(|node| node.level() == level.wrapping_sub(1));
+
+            if !is_valid {
+                return Err(invalid());
+            }
+        }
+
+        Ok(Self::Inner { level, children })
+    }
+
+    /// Returns the number of cells in the node, given the numbers of cells
+    /// in the nodes read before it.
+    fn count_cells(&self, counts: &[usize]) -> usize {
+        match self {
+            Self::Cells { cells, .. } => cells.len(),
+            Self::Inner { children, .. } => children
+                .iter()
+                .filter_map(|child| child.checked_sub(1).and_then(|idx| counts.get(idx)))
+                .fold(0, |total, count| total.saturating_add(*count)),
+        }
+    }
+
+    const fn level(&self) -> u32 {
+        match self {
+            Self::Cells { level, .. } | Self::Inner { level, .. } => *level,
+        }
+    }
+
+    /// Pushes the absolute positions of the node's cells, with its top-left
+    /// corner at `corner`.
+    fn expand(&self, nodes: &[Self], corner: (i64, i64), cells: &mut Vec<((i64, i64), u8)>) {
+        match self {
+            Self::Cells { cells: states, .. } => {
+                cells.extend(
+                    states.iter().map(|((x, y), state)| {
+                        ((offset(corner.0, *x), offset(corner.1, *y)), *state)
+                    }),
+                );
+            }
+            Self::Inner { level, children } => {
+                let half = half_size(*level);
+                let corners = [(0, 0), (half, 0), (0, half), (half, half)];
+
+                for (child, (x, y)) in children.iter().zip(corners) {
+                    // Children were checked to exist while reading
+                    if let Some(node) = child.checked_sub(1).and_then(|idx| nodes.get(idx)) {
+                        node.expand(
+                            nodes,
+                            (corner.0.wrapping_add(x), corner.1.wrapping_add(y)),
+                            cells,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Builds the (deduplicated) node lines of a pattern.
+struct Writer {
+    multistate: bool,
+    lines: Vec<String>,
+    /// Line numbers (i.e. node indices) of the lines written so far.
+    indices: HashMap<String, usize>,
+}
+
+impl Writer {
+    /// Writes the node containing `cells` and returns its index.
+    fn node(&mut self, cells: Vec<((i64, i64), u8)>, corner: (i64, i64), level: u32) -> usize {
+        if cells.is_empty() {
+            return 0;
+        }
+
+        let line = if level == 1 {
+            let mut states = [0; 4];
+
+            for ((x, y), state) in cells {
+                let idx = quadrant(x != corner.0, y != corner.1);
+
+                if let Some(cell) = states.get_mut(idx) {
+                    *cell = state;
+                }
+            }
+
+            let [nw, ne, sw, se] = states;
+
+            format!("1 {nw} {ne} {sw} {se}")
+        } else if level == LEAF_LEVEL && !self.multistate {
+            let mut rows = [[false; LEAF_SIZE]; LEAF_SIZE];
+
+            for ((x, y), _) in cells {
+                let (x, y) = (x.abs_diff(corner.0), y.abs_diff(corner.1));
+                let cell = usize::try_from(y)
+                    .ok()
+                    .and_then(|y| rows.get_mut(y))
+                    .zip(usize::try_from(x).ok())
+                    .and_then(|(row, x)| row.get_mut(x));
+
+                if let Some(cell) = cell {
+                    *cell = true;
+                }
+            }
+
+            let last_row = rows.iter().rposition(|row| row.contains(&true));
+            let mut line = String::new();
+
+            for row in rows
+                .iter()
+                .take(last_row.map_or(0, |y| y.saturating_add(1)))
+            {
+                let length = row.iter().rposition(|is_alive| *is_alive);
+                line.extend(
+                    row.iter()
+                        .take(length.map_or(0, |x| x.saturating_add(1)))
+                        .map(|is_alive| if *is_alive { '*' } else { '.' }),
+                );
+                line.push('$');
+            }
+
+            line
+        } else {
+            let half = half_size(level);
+            let middle = (corner.0.wrapping_add(half), corner.1.wrapping_add(half));
+            let mut quadrants: [Vec<_>; 4] = Default::default();
+
+            for cell @ ((x, y), _) in cells {
+                let idx = quadrant(x >= middle.0, y >= middle.1);
+
+                if let Some(quadrant) = quadrants.get_mut(idx) {
+                    quadrant.push(cell);
+                }
+            }
+
+            let corners = [corner, (middle.0, corner.1), (corner.0, middle.1), middle];
+            let mut children = [0; 4];
+
+            for (child, (cells, corner)) in
+                children.iter_mut().zip(quadrants.into_iter().zip(corners))
+            {
+                *child = self.node(cells, corner, level.wrapping_sub(1));
+            }
+
+            let [nw, ne, sw, se] = children;
+
+            format!("{level} {nw} {ne} {sw} {se}")
+        };
+
+        if let Some(idx) = self.indices.get(&line) {
+            return *idx;
+        }
+
+        self.lines.push(line.clone());
+        self.indices.insert(line, self.lines.len());
+
+        self.lines.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use super::{read, read_with_limit, write};
+    use crate::pattern::{ParseError, ParseErrorKind, Pattern};
+
+    #[test]
+    fn glider() {
+        let glider =
+            read("[M2] (golly 4.2)\n#R B3/S23\n#C Name: Glider\n.*$..*$***$\n4 0 0 0 1\n").unwrap();
+
+        assert_eq!(glider.name.as_deref(), Some("Glider"));
+        assert_eq!(glider.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(glider.origin, (0, 0));
+        assert_eq!(
+            glider.to_generic(),
+            [
+                [false, true, false],
+                [false, false, true],
+                [true, true, true]
+            ]
+        );
+        assert_eq!(read(&write(&glider)).unwrap(), glider);
+    }
+
+    #[test]
+    fn deduplication() {
+        // Four blocks at the same position in each quadrant
+        let blocks = [(-1000_i64, -1000_i64), (24, -1000), (-1000, 24), (24, 24)]
+            .into_iter()
+            .flat_map(|(x, y)| {
+                [(0, 0), (1, 0), (0, 1), (1, 1)]
+                    .map(|(dx, dy)| ((x.saturating_add(dx), y.saturating_add(dy)), 1))
+            });
+        let mut pattern = Pattern::default();
+        pattern.set_absolute_cells(blocks);
+
+        let output = write(&pattern);
+
+        // A single leaf and a single node per level
+        assert_eq!(output.lines().count(), 10);
+        assert_eq!(read(&output).unwrap(), pattern);
+    }
+
+    #[test]
+    fn multistate() {
+        let pattern = Pattern::new([((0, 0), 1), ((1, 0), 2), ((3, 2), 200)]);
+
+        assert_eq!(read(&write(&pattern)).unwrap(), pattern);
+        assert_eq!(
+            read("[M2]\n1 0 1 2 3\n2 0 0 0 1\n")
+                .unwrap()
+                .to_generic_states(),
+            [[0, 1], [2, 3]]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            read("x = 1, y = 1\no!"),
+            Err(ParseError::new(1, ParseErrorKind::MissingHeader))
+        );
+        assert_eq!(
+            read("[M2]\n.*$\n5 0 0 0 1"),
+            Err(ParseError::new(
+                3,
+                ParseErrorKind::InvalidLine("5 0 0 0 1".to_owned())
+            ))
+        );
+        assert_eq!(
+            read("[M2]\n.*$\n4 0 0 2 1"),
+            Err(ParseError::new(
+                3,
+                ParseErrorKind::InvalidLine("4 0 0 2 1".to_owned())
+            ))
+        );
+        assert_eq!(
+            read("[M2]\n.*o$"),
+            Err(ParseError::new(2, ParseErrorKind::UnexpectedChar('o')))
+        );
+        // The cell would lie in the next leaf
+        assert_eq!(
+            read("[M2]\n$$$$$$$$*"),
+            Err(ParseError::new(
+                2,
+                ParseErrorKind::InvalidLine("$$$$$$$$*".to_owned())
+            ))
+        );
+        assert_eq!(
+            read("[M2]\n........*$"),
+            Err(ParseError::new(
+                2,
+                ParseErrorKind::InvalidLine("........*$".to_owned())
+            ))
+        );
+    }
+
+    #[test]
+    fn cell_limit() {
+        // Each level doubles the number of cells
+        let mut input = "[M2]\n*$\n4 1 1 0 0\n".to_owned();
+
+        for level in 5..=40_u32 {
+            let _ = writeln!(input, "{level} {0} {0} 0 0", level.wrapping_sub(3));
+        }
+
+        assert_eq!(
+            read_with_limit(&input, 1 << 20),
+            Err(ParseError::new(39, ParseErrorKind::TooManyCells(1 << 20)))
+        );
+        assert!(read_with_limit("[M2]\n*$\n4 1 1 0 0\n5 2 2 0 0", 4).is_ok());
+    }
+}
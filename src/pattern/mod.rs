@@ -0,0 +1,316 @@
+//! Pattern file formats.
+//!
+//! All formats are read into and written from a [`Pattern`], which can be
+//! converted to and from the grids of both engines.
+//!
+//! Formats without dedicated metadata fields store a pattern's name and
+//! author as `Name: ...` and `Author: ...` comments.
+//!
+//! # Examples
+//! ```rust
+//! use lifers::pattern::rle;
+//!
+//! let glider = rle::read("x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+//! let cells = glider.to_life_like();
+//!
+//! assert_eq!(cells.len(), 5);
+//! assert_eq!(glider.rule.as_deref(), Some("B3/S23"));
+//! ```
+
+pub mod life105;
+pub mod life106;
+pub mod macrocell;
+pub mod plaintext;
+pub mod rle;
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::engine::{generic, life_like, Pos};
+
+/// A finite pattern of cells along with its metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Pattern {
+    /// Width and height of the pattern's bounding box.
+    pub size: (usize, usize),
+    /// Position of the bounding box's top-left corner.
+    ///
+    /// Most formats place patterns at the origin, but some allow arbitrary
+    /// (possibly negative) positions.
+    pub origin: (i64, i64),
+    /// States of non-empty cells, relative to [`origin`](Self::origin).
+    ///
+    /// Two-state patterns use `1` for alive cells.
+    pub cells: BTreeMap<Pos, u8>,
+    /// Rule the pattern is meant to be run with (e.g. `B3/S23`).
+    pub rule: Option<String>,
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+}
+
+impl Pattern {
+    /// Creates a pattern from the positions and states of non-empty cells.
+    ///
+    /// The bounding box is computed from the cells.
+    pub fn new<I: IntoIterator<Item = (Pos, u8)>>(cells: I) -> Self {
+        let cells: BTreeMap<_, _> = cells.into_iter().filter(|(_, state)| *state != 0).collect();
+        let size = cells.keys().fold((0, 0), |(width, height), (x, y)| {
+            (
+                width.max(x.saturating_add(1)),
+                height.max(y.saturating_add(1)),
+            )
+        });
+
+        Self {
+            size,
+            cells,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a pattern from a sparse grid, treating all cells as alive.
+    ///
+    /// The position of the grid's bounding box is kept as the
+    /// [`origin`](Self::origin).
+    pub fn from_life_like<S, H>(grid: &life_like::Grid<S, H>) -> Self {
+        Self::from_positions(grid.keys().map(|pos| (*pos, 1)))
+    }
+
+    /// Creates a pattern from a sparse grid of multistate cells.
+    pub fn from_life_like_states<H>(grid: &life_like::Grid<u8, H>) -> Self {
+        Self::from_positions(grid.iter().map(|(pos, state)| (*pos, *state)))
+    }
+
+    /// Creates a pattern from a dense grid of alive (`true`) and dead cells.
+    pub fn from_generic(grid: &generic::Grid<bool>) -> Self {
+        Self::from_generic_with(grid, |is_alive| u8::from(*is_alive))
+    }
+
+    /// Creates a pattern from a dense grid of multistate cells.
+    pub fn from_generic_states(grid: &generic::Grid<u8>) -> Self {
+        Self::from_generic_with(grid, |state| *state)
+    }
+
+    /// Returns `true` if any cell has a state other than 0 and 1.
+    pub fn is_multistate(&self) -> bool {
+        self.cells.values().any(|state| *state > 1)
+    }
+
+    /// Returns the pattern as a sparse grid of alive cells, with the
+    /// bounding box's top-left corner at the [`origin`](Self::origin).
+    pub fn to_life_like(&self) -> life_like::Grid<()> {
+        self.absolute_cells().map(|(pos, _)| (pos, ())).collect()
+    }
+
+    /// Like [`to_life_like()`](Self::to_life_like), but keeps cell states.
+    pub fn to_life_like_states(&self) -> life_like::Grid<u8> {
+        self.absolute_cells().collect()
+    }
+
+    /// Returns the pattern as a dense grid the size of its bounding box.
+    pub fn to_generic(&self) -> generic::Grid<bool> {
+        self.to_generic_with(false, |_| true)
+    }
+
+    /// Like [`to_generic()`](Self::to_generic), but keeps cell states.
+    pub fn to_generic_states(&self) -> generic::Grid<u8> {
+        self.to_generic_with(0, |state| state)
+    }
+
+    /// Creates a pattern from cells at absolute positions.
+    fn from_positions<I: Iterator<Item = (Pos<i64>, u8)>>(cells: I) -> Self {
+        let mut pattern = Self::default();
+        pattern.set_absolute_cells(cells);
+
+        pattern
+    }
+
+    /// Replaces the cells with ones at absolute positions, updating the
+    /// bounding box and origin.
+    pub(crate) fn set_absolute_cells<I: IntoIterator<Item = ((i64, i64), u8)>>(
+        &mut self,
+        cells: I,
+    ) {
+        let cells: Vec<_> = cells.into_iter().filter(|(_, state)| *state != 0).collect();
+        let origin = cells
+            .iter()
+            .map(|(pos, _)| *pos)
+            .reduce(|(min_x, min_y), (x, y)| (min_x.min(x), min_y.min(y)))
+            .unwrap_or_default();
+        let relative = Self::new(
+            cells
+                .into_iter()
+                .map(|((x, y), state)| ((distance(origin.0, x), distance(origin.1, y)), state)),
+        );
+
+        self.origin = origin;
+        self.size = relative.size;
+        self.cells = relative.cells;
+    }
+
+    /// Returns the non-empty cells at their absolute positions.
+    pub(crate) fn absolute_cells(&self) -> impl Iterator<Item = ((i64, i64), u8)> + '_ {
+        self.cells.iter().map(|((x, y), state)| {
+            (
+                (offset(self.origin.0, *x), offset(self.origin.1, *y)),
+                *state,
+            )
+        })
+    }
+
+    /// Stores a comment, recognizing the `Name: ...` and `Author: ...`
+    /// conventions.
+    pub(crate) fn read_comment(&mut self, comment: &str) {
+        let comment = comment.trim();
+
+        if let Some(name) = comment.strip_prefix("Name:") {
+            self.name = Some(name.trim().to_owned());
+        } else if let Some(author) = comment.strip_prefix("Author:") {
+            self.author = Some(author.trim().to_owned());
+        } else {
+            self.comments.push(comment.to_owned());
+        }
+    }
+
+    /// Returns the name, author and comments as comment lines, the inverse
+    /// of [`read_comment()`](Self::read_comment).
+    pub(crate) fn comment_lines(&self) -> Vec<String> {
+        self.name
+            .iter()
+            .map(|name| format!("Name: {name}"))
+            .chain(self.author.iter().map(|author| format!("Author: {author}")))
+            .chain(self.comments.iter().cloned())
+            .collect()
+    }
+
+    fn from_generic_with<S, F: Fn(&S) -> u8>(grid: &generic::Grid<S>, f: F) -> Self {
+        let mut pattern =
+            Self::new(generic::iter_grid(grid).map(|(x, y, state)| ((x, y), f(state))));
+        pattern.size = (grid.first().map_or(0, Vec::len), grid.len());
+
+        pattern
+    }
+
+    fn to_generic_with<S: Clone, F: Fn(u8) -> S>(&self, empty: S, f: F) -> generic::Grid<S> {
+        let (width, height) = self.size;
+        let mut grid = vec![vec![empty; width]; height];
+
+        for ((x, y), state) in &self.cells {
+            if let Some(cell) = grid.get_mut(*y).and_then(|xs| xs.get_mut(*x)) {
+                *cell = f(*state);
+            }
+        }
+
+        grid
+    }
+
+    /// Returns the non-empty cells grouped by rows, in reading order.
+    fn rows(&self) -> BTreeMap<usize, Vec<(usize, u8)>> {
+        let mut rows: BTreeMap<usize, Vec<_>> = BTreeMap::new();
+
+        for ((x, y), state) in &self.cells {
+            rows.entry(*y).or_default().push((*x, *state));
+        }
+
+        for row in rows.values_mut() {
+            row.sort_unstable();
+        }
+
+        rows
+    }
+}
+
+/// An error encountered while parsing a pattern file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The (1-based) line number the error occured at.
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input is missing a required header.
+    MissingHeader,
+    /// The header is malformed.
+    InvalidHeader(String),
+    /// A character that isn't allowed at this position.
+    UnexpectedChar(char),
+    /// A number (count, coordinate or size) is malformed or too big.
+    InvalidNumber(String),
+    /// A line that doesn't fit the format.
+    InvalidLine(String),
+    /// The input ended unexpectedly.
+    UnexpectedEnd,
+    /// The pattern has more cells than the given limit.
+    TooManyCells(usize),
+}
+
+impl ParseError {
+    pub(crate) const fn new(line: usize, kind: ParseErrorKind) -> Self {
+        Self { line, kind }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match &self.kind {
+            ParseErrorKind::MissingHeader => write!(f, "missing header"),
+            ParseErrorKind::InvalidHeader(header) => write!(f, "invalid header `{header}`"),
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            ParseErrorKind::InvalidNumber(n) => write!(f, "invalid number `{n}`"),
+            ParseErrorKind::InvalidLine(line) => write!(f, "invalid line `{line}`"),
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseErrorKind::TooManyCells(limit) => write!(f, "more than {limit} cells"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Returns the trimmed lines of `input` along with their (1-based) numbers.
+pub(crate) fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx.saturating_add(1), line.trim()))
+}
+
+/// Checks that the first non-empty line is the given `#Life` header.
+pub(crate) fn read_life_header<'a, I: Iterator<Item = (usize, &'a str)>>(
+    lines: &mut I,
+    header: &str,
+    input: &str,
+) -> Result<(), ParseError> {
+    match lines.find(|(_, line)| !line.is_empty()) {
+        Some((_, line)) if line == header => Ok(()),
+        Some((n, line)) if line.starts_with("#Life") => Err(ParseError::new(
+            n,
+            ParseErrorKind::InvalidHeader(line.to_owned()),
+        )),
+        Some((n, _)) => Err(ParseError::new(n, ParseErrorKind::MissingHeader)),
+        None => Err(ParseError::new(
+            input.lines().count(),
+            ParseErrorKind::MissingHeader,
+        )),
+    }
+}
+
+pub(crate) fn parse_number<T: std::str::FromStr>(number: &str, n: usize) -> Result<T, ParseError> {
+    number
+        .parse()
+        .map_err(|_| ParseError::new(n, ParseErrorKind::InvalidNumber(number.to_owned())))
+}
+
+/// Returns the position `distance` cells after `origin`.
+pub(crate) fn offset(origin: i64, distance: usize) -> i64 {
+    origin.saturating_add_unsigned(u64::try_from(distance).unwrap_or(u64::MAX))
+}
+
+/// Returns the distance between two positions.
+fn distance(from: i64, to: i64) -> usize {
+    usize::try_from(to.abs_diff(from)).unwrap_or(usize::MAX)
+}
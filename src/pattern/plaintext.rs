@@ -0,0 +1,112 @@
+//! The [plaintext](https://conwaylife.com/wiki/Plaintext) (`.cells`) pattern
+//! format.
+//!
+//! Only two-state patterns can be represented, so all non-empty cells are
+//! written as alive.
+
+use std::fmt::Write;
+
+use crate::pattern::{numbered_lines, ParseError, ParseErrorKind, Pattern};
+
+/// Parses a plaintext pattern.
+///
+/// Both `O` and `*` are accepted for alive cells.
+///
+/// # Errors
+/// Returns an error if a row contains a character other than `.`, `O` or `*`.
+pub fn read(input: &str) -> Result<Pattern, ParseError> {
+    let mut pattern = Pattern::default();
+    let (mut width, mut height) = (0_usize, 0_usize);
+
+    for (n, line) in numbered_lines(input) {
+        if let Some(comment) = line.strip_prefix('!') {
+            pattern.read_comment(comment);
+            continue;
+        }
+
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                '.' => (),
+                'O' | '*' => {
+                    pattern.cells.insert((x, height), 1);
+                }
+                c => return Err(ParseError::new(n, ParseErrorKind::UnexpectedChar(c))),
+            }
+        }
+
+        width = width.max(line.chars().count());
+        height = height.saturating_add(1);
+    }
+
+    pattern.size = (width, height);
+
+    Ok(pattern)
+}
+
+/// Writes a pattern in the plaintext format.
+///
+/// Every row is written out to the full width of the pattern.
+pub fn write(pattern: &Pattern) -> String {
+    let mut output = String::new();
+
+    for comment in pattern.comment_lines() {
+        let _ = writeln!(output, "!{comment}");
+    }
+
+    let (width, height) = pattern.size;
+
+    for y in 0..height {
+        output.extend((0..width).map(|x| {
+            if pattern.cells.contains_key(&(x, y)) {
+                'O'
+            } else {
+                '.'
+            }
+        }));
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read, write};
+    use crate::pattern::{ParseError, ParseErrorKind};
+
+    const GLIDER: &str =
+        "!Name: Glider\n!Author: Richard K. Guy\n!The smallest spaceship\n.O.\n..O\nOOO\n";
+
+    #[test]
+    fn glider() {
+        let glider = read(GLIDER).unwrap();
+
+        assert_eq!(glider.name.as_deref(), Some("Glider"));
+        assert_eq!(glider.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(glider.comments, ["The smallest spaceship"]);
+        assert_eq!(glider.size, (3, 3));
+        assert_eq!(
+            glider.to_generic(),
+            [
+                [false, true, false],
+                [false, false, true],
+                [true, true, true]
+            ]
+        );
+        assert_eq!(write(&glider), GLIDER);
+
+        // Short rows and empty lines are allowed
+        let blinker = read("*\n*\n\n*").unwrap();
+
+        assert_eq!(blinker.size, (1, 4));
+        assert_eq!(blinker.cells.len(), 3);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            read("!Comment\n.O.\n.#."),
+            Err(ParseError::new(3, ParseErrorKind::UnexpectedChar('#')))
+        );
+    }
+}
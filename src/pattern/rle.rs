@@ -0,0 +1,454 @@
+//! The [RLE](https://conwaylife.com/wiki/Run_Length_Encoded) pattern format.
+//!
+//! Both two-state (`b`/`o`) and multistate (`.`/`A`..`X`/`pA`..`yO`) cells
+//! are supported. Multistate patterns are written using the latter.
+
+use std::fmt::Write;
+
+use crate::pattern::{
+    macrocell::DEFAULT_MAX_CELLS, numbered_lines, parse_number, ParseError, ParseErrorKind, Pattern,
+};
+
+/// Maximum length of a line produced by [`write()`].
+pub const LINE_LENGTH: usize = 70;
+
+/// Number of states encoded by a single letter suffix (`A`..`X`).
+const SUFFIX_STATES: u8 = 24;
+
+/// Parses an RLE pattern with at most [`DEFAULT_MAX_CELLS`] cells.
+///
+/// # Errors
+/// See [`read_with_limit()`].
+pub fn read(input: &str) -> Result<Pattern, ParseError> {
+    read_with_limit(input, DEFAULT_MAX_CELLS)
+}
+
+/// Parses an RLE pattern with at most `max_cells` cells.
+///
+/// A single run count can describe billions of cells, so the limit is
+/// checked before a run is expanded.
+///
+/// # Errors
+/// Returns an error if the header is missing or malformed, the pattern
+/// data contains invalid characters or numbers, or the pattern has more
+/// than `max_cells` cells.
+pub fn read_with_limit(input: &str, max_cells: usize) -> Result<Pattern, ParseError> {
+    let mut pattern = Pattern::default();
+    let mut lines = numbered_lines(input);
+    let mut header_size = None;
+
+    for (n, line) in lines.by_ref() {
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(metadata) = line.strip_prefix('#') {
+            read_metadata(&mut pattern, metadata, n)?;
+        } else {
+            let (size, rule) = read_header(line, n)?;
+
+            header_size = Some(size);
+            pattern.rule = rule;
+            break;
+        }
+    }
+
+    let Some(header_size) = header_size else {
+        return Err(ParseError::new(
+            input.lines().count(),
+            ParseErrorKind::MissingHeader,
+        ));
+    };
+
+    let mut reader = BodyReader {
+        max_cells,
+        ..BodyReader::default()
+    };
+
+    for (n, line) in lines {
+        if reader.read_line(&mut pattern, line, n)? {
+            break;
+        }
+    }
+
+    pattern.size = (
+        pattern.size.0.max(header_size.0),
+        pattern.size.1.max(header_size.1),
+    );
+
+    Ok(pattern)
+}
+
+/// Writes a pattern in the RLE format, wrapping lines at [`LINE_LENGTH`].
+pub fn write(pattern: &Pattern) -> String {
+    let mut output = String::new();
+
+    if let Some(name) = &pattern.name {
+        let _ = writeln!(output, "#N {name}");
+    }
+
+    if let Some(author) = &pattern.author {
+        let _ = writeln!(output, "#O {author}");
+    }
+
+    for comment in &pattern.comments {
+        let _ = writeln!(output, "#C {comment}");
+    }
+
+    if pattern.origin != (0, 0) {
+        let _ = writeln!(
+            output,
+            "#CXRLE Pos={},{}",
+            pattern.origin.0, pattern.origin.1
+        );
+    }
+
+    let _ = write!(output, "x = {}, y = {}", pattern.size.0, pattern.size.1);
+
+    if let Some(rule) = &pattern.rule {
+        let _ = write!(output, ", rule = {rule}");
+    }
+
+    output.push('\n');
+
+    let mut line_length = 0_usize;
+
+    for token in tokens(pattern) {
+        if line_length.saturating_add(token.len()) > LINE_LENGTH {
+            output.push('\n');
+            line_length = 0;
+        }
+
+        output.push_str(&token);
+        line_length = line_length.saturating_add(token.len());
+    }
+
+    output.push('\n');
+
+    output
+}
+
+/// Reads a `#` line (without the `#`).
+fn read_metadata(pattern: &mut Pattern, line: &str, n: usize) -> Result<(), ParseError> {
+    let (tag, value) = line.split_once(' ').unwrap_or((line, ""));
+    let value = value.trim();
+
+    match tag {
+        "N" => pattern.name = Some(value.to_owned()),
+        "O" => pattern.author = Some(value.to_owned()),
+        "C" | "c" => pattern.comments.push(value.to_owned()),
+        "r" => pattern.rule = Some(value.to_owned()),
+        "P" | "R" => {
+            let (x, y) = value.split_once(char::is_whitespace).ok_or_else(|| {
+                ParseError::new(n, ParseErrorKind::InvalidHeader(line.to_owned()))
+            })?;
+
+            pattern.origin = (parse_number(x, n)?, parse_number(y.trim(), n)?);
+        }
+        "CXRLE" => {
+            let position = value
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix("Pos="))
+                .and_then(|position| position.split_once(','));
+
+            if let Some((x, y)) = position {
+                pattern.origin = (parse_number(x, n)?, parse_number(y, n)?);
+            }
+        }
+        // Other lines are not meaningful for us
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Reads the `x = m, y = n[, rule = abc]` line.
+///
+/// The rule is taken verbatim up to the end of the line, since it may
+/// contain commas (e.g. bounded grids, like `B3/S23:T10,10`).
+fn read_header(line: &str, n: usize) -> Result<((usize, usize), Option<String>), ParseError> {
+    let invalid = || ParseError::new(n, ParseErrorKind::InvalidHeader(line.to_owned()));
+    let (mut width, mut height, mut rule) = (None, None, None);
+    let mut rest = line;
+
+    while !rest.trim().is_empty() {
+        let (key, value) = rest.split_once('=').ok_or_else(invalid)?;
+
+        if key.trim() == "rule" {
+            rule = Some(value.trim().to_owned());
+            break;
+        }
+
+        let (value, next) = value.split_once(',').unwrap_or((value, ""));
+
+        match key.trim() {
+            "x" => width = Some(parse_number(value.trim(), n)?),
+            "y" => height = Some(parse_number(value.trim(), n)?),
+            _ => return Err(invalid()),
+        }
+
+        rest = next;
+    }
+
+    Ok((width.zip(height).ok_or_else(invalid)?, rule))
+}
+
+/// State of the pattern data parser, which is kept between lines.
+#[derive(Default)]
+struct BodyReader {
+    x: usize,
+    y: usize,
+    count: Option<usize>,
+    /// Multistate prefix (`p`..`y`) waiting for its suffix.
+    prefix: Option<u8>,
+    max_cells: usize,
+}
+
+impl BodyReader {
+    /// Reads a line of pattern data, returning `true` if the end of the
+    /// pattern (`!`) was reached.
+    fn read_line(
+        &mut self,
+        pattern: &mut Pattern,
+        line: &str,
+        n: usize,
+    ) -> Result<bool, ParseError> {
+        let error = |kind| ParseError::new(n, kind);
+
+        for c in line.chars() {
+            if self.prefix.is_some() && !c.is_ascii_uppercase() {
+                return Err(error(ParseErrorKind::UnexpectedChar(c)));
+            }
+
+            match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).map_or(0, |d| d as usize);
+                    let count = self
+                        .count
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|count| count.checked_add(digit))
+                        .ok_or_else(|| error(ParseErrorKind::InvalidNumber(line.to_owned())))?;
+
+                    self.count = Some(count);
+                }
+                'b' | '.' => {
+                    let count = self.take_count();
+                    self.x = self.x.saturating_add(count);
+                }
+                'o' => self.push_cells(pattern, 1).map_err(error)?,
+                'A'..='X' => {
+                    // `c` is in `A..=X`, so this can't wrap
+                    let suffix = (c as u8).wrapping_sub(b'A');
+                    let state = self
+                        .prefix
+                        .take()
+                        .map_or(Some(0), |prefix| prefix.checked_mul(SUFFIX_STATES))
+                        .and_then(|state| state.checked_add(suffix))
+                        .and_then(|state| state.checked_add(1))
+                        .ok_or_else(|| error(ParseErrorKind::UnexpectedChar(c)))?;
+
+                    self.push_cells(pattern, state).map_err(error)?;
+                }
+                'p'..='y' => self.prefix = Some((c as u8).wrapping_sub(b'p').wrapping_add(1)),
+                '$' => {
+                    let count = self.take_count();
+                    self.y = self.y.saturating_add(count);
+                    self.x = 0;
+                }
+                '!' => return Ok(true),
+                c if c.is_whitespace() => (),
+                c => return Err(error(ParseErrorKind::UnexpectedChar(c))),
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn take_count(&mut self) -> usize {
+        self.count.take().unwrap_or(1)
+    }
+
+    fn push_cells(&mut self, pattern: &mut Pattern, state: u8) -> Result<(), ParseErrorKind> {
+        let count = self.take_count();
+
+        if pattern.cells.len().saturating_add(count) > self.max_cells {
+            return Err(ParseErrorKind::TooManyCells(self.max_cells));
+        }
+
+        for _ in 0..count {
+            pattern.cells.insert((self.x, self.y), state);
+            self.x = self.x.saturating_add(1);
+        }
+
+        pattern.size = (
+            pattern.size.0.max(self.x),
+            pattern.size.1.max(self.y.saturating_add(1)),
+        );
+
+        Ok(())
+    }
+}
+
+/// Returns the pattern data split into tokens that can't be broken across
+/// lines.
+fn tokens(pattern: &Pattern) -> Vec<String> {
+    let multistate = pattern.is_multistate();
+    let mut tokens = Vec::new();
+    let mut y = 0;
+
+    for (row_y, row) in pattern.rows() {
+        if row_y > y {
+            tokens.push(run(row_y.saturating_sub(y), "$"));
+        }
+
+        y = row_y;
+
+        // Merge adjacent cells into runs of (state, length)
+        let mut runs: Vec<(u8, usize)> = Vec::new();
+        let mut x = 0;
+
+        for (cell_x, state) in row {
+            if cell_x > x {
+                runs.push((0, cell_x.saturating_sub(x)));
+            }
+
+            match runs.last_mut() {
+                Some((last, length)) if *last == state => *length = length.saturating_add(1),
+                _ => runs.push((state, 1)),
+            }
+
+            x = cell_x.saturating_add(1);
+        }
+
+        tokens.extend(
+            runs.into_iter()
+                .map(|(state, length)| run(length, &symbol(state, multistate))),
+        );
+    }
+
+    tokens.push("!".to_owned());
+
+    tokens
+}
+
+fn run(length: usize, symbol: &str) -> String {
+    if length == 1 {
+        symbol.to_owned()
+    } else {
+        format!("{length}{symbol}")
+    }
+}
+
+/// Returns the symbol used for a cell state.
+fn symbol(state: u8, multistate: bool) -> String {
+    match (state, multistate) {
+        (0, false) => "b".to_owned(),
+        (_, false) => "o".to_owned(),
+        (0, true) => ".".to_owned(),
+        (_, true) => {
+            // `state` is at least 1 and at most 255, so none of this can wrap
+            let index = state.wrapping_sub(1);
+            let (prefix, suffix) = (index / SUFFIX_STATES, index % SUFFIX_STATES);
+            let suffix = char::from(b'A'.wrapping_add(suffix));
+
+            match prefix {
+                0 => suffix.to_string(),
+                _ => format!("{}{suffix}", char::from(b'o'.wrapping_add(prefix))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read, read_with_limit, write, DEFAULT_MAX_CELLS};
+    use crate::pattern::{ParseError, ParseErrorKind, Pattern};
+
+    const GLIDER: &str =
+        "#N Glider\n#C A small spaceship\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+
+    #[test]
+    fn glider() {
+        let glider = read(GLIDER).unwrap();
+
+        assert_eq!(glider.name.as_deref(), Some("Glider"));
+        assert_eq!(glider.comments, ["A small spaceship"]);
+        assert_eq!(glider.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(glider.size, (3, 3));
+        assert_eq!(
+            glider.to_generic(),
+            [
+                [false, true, false],
+                [false, false, true],
+                [true, true, true]
+            ]
+        );
+        assert_eq!(write(&glider), GLIDER);
+    }
+
+    #[test]
+    fn multistate() {
+        let pattern = read("x = 5, y = 2, rule = Foo\n.A2B$3.pAyO!").unwrap();
+
+        assert!(pattern.is_multistate());
+        assert_eq!(
+            pattern.to_generic_states(),
+            [[0, 1, 2, 2, 0], [0, 0, 0, 25, 255]]
+        );
+        assert_eq!(read(&write(&pattern)).unwrap(), pattern);
+    }
+
+    #[test]
+    fn bounded_grid() {
+        let pattern = read("x = 3, y = 3, rule = B3/S23:T10,10\nbo$2bo$3o!").unwrap();
+
+        assert_eq!(pattern.rule.as_deref(), Some("B3/S23:T10,10"));
+        assert_eq!(pattern.size, (3, 3));
+        assert_eq!(read(&write(&pattern)).unwrap(), pattern);
+    }
+
+    #[test]
+    fn wrapping() {
+        let pattern = Pattern::new((0..200_usize).map(|x| ((x.saturating_mul(2), x % 3), 1)));
+        let output = write(&pattern);
+
+        assert!(output.lines().all(|line| line.len() <= super::LINE_LENGTH));
+        assert_eq!(read(&output).unwrap(), pattern);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            read("#C Only a comment"),
+            Err(ParseError::new(1, ParseErrorKind::MissingHeader))
+        );
+        assert_eq!(
+            read("x = 1\no!"),
+            Err(ParseError::new(
+                1,
+                ParseErrorKind::InvalidHeader("x = 1".to_owned())
+            ))
+        );
+        assert_eq!(
+            read("x = 1, y = 1\n\nbz!"),
+            Err(ParseError::new(3, ParseErrorKind::UnexpectedChar('z')))
+        );
+    }
+
+    #[test]
+    fn too_many_cells() {
+        assert_eq!(
+            read("x = 1, y = 1\n18446744073709551615o!"),
+            Err(ParseError::new(
+                2,
+                ParseErrorKind::TooManyCells(DEFAULT_MAX_CELLS)
+            ))
+        );
+        assert_eq!(
+            read_with_limit("x = 3, y = 2\n2o$2o!", 3),
+            Err(ParseError::new(2, ParseErrorKind::TooManyCells(3)))
+        );
+        assert!(read_with_limit("x = 3, y = 2\n2o$o!", 3).is_ok());
+    }
+}